@@ -0,0 +1,114 @@
+use crate::{
+    reader::{Object, Value, ValueReader},
+    Error, Result,
+};
+
+use bson::Bson;
+use std::borrow::Cow;
+
+/// Reads a stream of concatenated BSON documents, as written by `mongodump`.
+pub struct Reader<R: AsRef<[u8]>> {
+    offset: usize,
+    reader: R,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {}
+
+fn from_document(document: bson::Document) -> Value<'static> {
+    Value::Object(
+        document
+            .into_iter()
+            .map(|(key, value)| (Cow::Owned(key), from_bson(value)))
+            .collect::<Object>(),
+    )
+}
+
+fn from_bson(value: Bson) -> Value<'static> {
+    match value {
+        Bson::Double(f) => Value::Float(f),
+        Bson::String(s) | Bson::Symbol(s) | Bson::JavaScriptCode(s) => Value::String(Cow::Owned(s)),
+        Bson::JavaScriptCodeWithScope(code) => Value::String(Cow::Owned(code.code)),
+        Bson::Array(values) => Value::Array(values.into_iter().map(from_bson).collect()),
+        Bson::Document(document) => from_document(document),
+        Bson::Boolean(b) => Value::Bool(b),
+        Bson::Null | Bson::Undefined | Bson::MaxKey | Bson::MinKey | Bson::DbPointer(_) => {
+            Value::Null
+        }
+        Bson::RegularExpression(regex) => {
+            Value::String(Cow::Owned(format!("/{}/{}", regex.pattern, regex.options)))
+        }
+        Bson::Int32(i) => Value::Int(i as i64),
+        Bson::Int64(i) => Value::Int(i),
+        Bson::Timestamp(ts) => Value::Int(ts.time as i64),
+        Bson::Binary(binary) => Value::Binary(binary.bytes),
+        Bson::ObjectId(oid) => Value::String(Cow::Owned(oid.to_hex())),
+        // Milliseconds since the Unix epoch
+        Bson::DateTime(dt) => Value::Int(dt.timestamp_millis()),
+        // Strings keep every digit, as with `$numberDecimal` in extended JSON
+        Bson::Decimal128(d) => Value::String(Cow::Owned(d.to_string())),
+    }
+}
+
+impl<R: AsRef<[u8]> + Send + Sync> ValueReader for Reader<R> {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let mut slice = &self.reader.as_ref()[self.offset..];
+
+        if slice.is_empty() {
+            return Ok(None);
+        }
+
+        let document = bson::Document::from_reader(&mut slice).map_err(Error::input)?;
+        self.offset = self.reader.as_ref().len() - slice.len();
+
+        Ok(Some(from_document(document)))
+    }
+
+    fn reset(&mut self) {
+        self.offset = 0;
+    }
+}
+
+impl<R: AsRef<[u8]> + Send + Sync> Reader<R> {
+    pub fn new(inner: R, _opts: Options) -> Result<Self> {
+        Ok(Self {
+            offset: 0,
+            reader: inner,
+        })
+    }
+}
+
+#[test]
+fn bson_read() {
+    use bson::{doc, oid::ObjectId, Decimal128};
+
+    let mut input = vec![];
+    let id = ObjectId::parse_str("5f5e1a6b9c1d2e3f4a5b6c7d").unwrap();
+    let price = "1.10".parse::<Decimal128>().unwrap();
+    let created = bson::DateTime::from_millis(1600000000000);
+    doc! {"_id": id, "price": price, "created": created, "tags": ["a", 1_i32]}
+        .to_writer(&mut input)
+        .unwrap();
+    doc! {"_id": 2_i64, "nested": {"ok": true, "none": null}}
+        .to_writer(&mut input)
+        .unwrap();
+
+    let mut reader = Reader::new(input, Options {}).unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({
+            "_id": "5f5e1a6b9c1d2e3f4a5b6c7d",
+            "price": "1.10",
+            "created": 1600000000000,
+            "tags": ["a", 1]
+        })
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"_id": 2, "nested": {"ok": true, "none": null}})
+    );
+    assert!(reader.next().unwrap().is_none());
+
+    reader.reset();
+    assert!(reader.next().unwrap().is_some());
+}
@@ -0,0 +1,68 @@
+use crate::reader::ValueReader;
+use crate::{Error, Result, Value};
+
+use bson::{spec::BinarySubtype, Binary, Bson, Document};
+use std::io::Write;
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {}
+
+fn to_bson(value: Value) -> Bson {
+    match value {
+        Value::Null => Bson::Null,
+        Value::Bool(b) => Bson::Boolean(b),
+        Value::String(s) => Bson::String(s.into_owned()),
+        Value::Int(i) => Bson::Int64(i),
+        Value::Float(f) => Bson::Double(f),
+        Value::Array(values) => Bson::Array(values.into_iter().map(to_bson).collect()),
+        Value::Object(object) => Bson::Document(
+            object
+                .into_iter()
+                .map(|(key, value)| (key.into_owned(), to_bson(value)))
+                .collect::<Document>(),
+        ),
+        Value::Binary(bytes) => Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes,
+        }),
+    }
+}
+
+pub fn write<W: Write, R: ValueReader>(mut inner: W, mut reader: R, _opts: Options) -> Result<()> {
+    while let Some(row) = reader.next()? {
+        let Bson::Document(document) = to_bson(row) else {
+            return Err(Error::OutputError(
+                "BSON output requires every record to be an object".into(),
+            ));
+        };
+
+        document.to_writer(&mut inner).map_err(Error::output)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bson_write() {
+    use crate::bson::reader::Reader;
+
+    let input = r#"{"id":1,"name":"a","tags":["x",2.5],"owner":{"admin":true},"note":null}"#;
+    let write_with = |input: &'static str| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, Options {}).map(|_| out)
+    };
+
+    let out = write_with(input).unwrap();
+    let document = Document::from_reader(&mut out.as_slice()).unwrap();
+    assert_eq!(document.get_i64("id").unwrap(), 1);
+
+    let mut reader = Reader::new(out, Default::default()).unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 1, "name": "a", "tags": ["x", 2.5], "owner": {"admin": true}, "note": null})
+    );
+
+    assert!(write_with("[1]").is_err());
+}
@@ -0,0 +1,253 @@
+use crate::{
+    reader::{Object, Value, ValueReader},
+    Error, Result,
+};
+
+use ciborium::value::Value as CborValue;
+use std::borrow::Cow;
+
+/// Reads a sequence of concatenated CBOR data items, each one being a record.
+pub struct Reader<R: AsRef<[u8]>> {
+    offset: usize,
+    reader: R,
+    mode: Mode,
+    buffer: std::vec::IntoIter<Value<'static>>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Mode {
+    /// Top-level arrays are unwrapped into one record per item
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "stream")]
+    Stream,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+/// Digits of a big number tag, from its big-endian magnitude.
+fn bignum_digits(bytes: &[u8]) -> String {
+    let mut digits = vec![];
+    let mut magnitude = bytes.to_vec();
+
+    while magnitude.iter().any(|b| *b != 0) {
+        let mut remainder = 0u32;
+        for byte in magnitude.iter_mut() {
+            let value = (remainder << 8) | *byte as u32;
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+fn increment(mut bytes: Vec<u8>) -> Vec<u8> {
+    for byte in bytes.iter_mut().rev() {
+        if *byte == 0xff {
+            *byte = 0;
+        } else {
+            *byte += 1;
+            return bytes;
+        }
+    }
+
+    bytes.insert(0, 1);
+    bytes
+}
+
+/// Converts a CBOR value, integers that don't fit in 64 bits and big numbers
+/// become strings so no digit is lost. Dates, URIs and the other tags whose
+/// value reads as is give their inner value, unknown tags
+/// `{"tag": <tag>, "value": <inner value>}`.
+fn from_cbor(value: CborValue) -> Result<Value<'static>> {
+    Ok(match value {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(b) => Value::Bool(b),
+        CborValue::Integer(i) => match i64::try_from(i) {
+            Ok(i) => Value::Int(i),
+            Err(_) => Value::String(Cow::Owned(i128::from(i).to_string())),
+        },
+        CborValue::Float(f) => Value::Float(f),
+        CborValue::Text(s) => Value::String(Cow::Owned(s)),
+        CborValue::Bytes(b) => Value::Binary(b),
+        CborValue::Tag(tag @ (2 | 3), inner) => match *inner {
+            // Negative ones are -1 - n
+            CborValue::Bytes(bytes) if tag == 3 => {
+                Value::String(Cow::Owned(format!("-{}", bignum_digits(&increment(bytes)))))
+            }
+            CborValue::Bytes(bytes) => Value::String(Cow::Owned(bignum_digits(&bytes))),
+            other => {
+                return Err(Error::InputError(format!(
+                    "Invalid CBOR big number: {:?}",
+                    other
+                )))
+            }
+        },
+        // Date strings, epoch times, URIs, base64 and regular expressions
+        CborValue::Tag(0 | 1 | 21..=24 | 32..=35, inner) => from_cbor(*inner)?,
+        CborValue::Tag(tag, inner) => Value::Object(
+            [
+                (Cow::Borrowed("tag"), Value::Int(tag as i64)),
+                (Cow::Borrowed("value"), from_cbor(*inner)?),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+        CborValue::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(from_cbor)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        CborValue::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match from_cbor(key)? {
+                        Value::String(s) => s,
+                        other => Cow::Owned(serde_json::to_string(&other)?),
+                    };
+                    Ok((key, from_cbor(value)?))
+                })
+                .collect::<Result<Object>>()?,
+        ),
+        other => {
+            return Err(Error::InputError(format!(
+                "Unsupported CBOR value: {:?}",
+                other
+            )))
+        }
+    })
+}
+
+impl<R: AsRef<[u8]> + Send + Sync> ValueReader for Reader<R> {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        loop {
+            if let Some(value) = self.buffer.next() {
+                return Ok(Some(value));
+            }
+
+            let mut slice = &self.reader.as_ref()[self.offset..];
+
+            if slice.is_empty() {
+                return Ok(None);
+            }
+
+            let value: CborValue = ciborium::de::from_reader(&mut slice).map_err(Error::input)?;
+            self.offset = self.reader.as_ref().len() - slice.len();
+
+            match (&self.mode, from_cbor(value)?) {
+                (Mode::Auto, Value::Array(values)) => self.buffer = values.into_iter(),
+                (_, value) => return Ok(Some(value)),
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.offset = 0;
+        self.buffer = vec![].into_iter();
+    }
+}
+
+impl<R: AsRef<[u8]> + Send + Sync> Reader<R> {
+    pub fn new(inner: R, opts: Options) -> Result<Self> {
+        Ok(Self {
+            offset: 0,
+            reader: inner,
+            mode: opts.mode,
+            buffer: vec![].into_iter(),
+        })
+    }
+}
+
+#[test]
+fn cbor_roundtrip() {
+    let mut data = vec![];
+
+    crate::cbor::writer::write(
+        &mut data,
+        crate::json::reader::RecordReader::new(
+            r#"[{"id": 1, "ratio": 1.0, "name": "a"}, {"id": 2, "ratio": 0.5, "name": null}]"#,
+            Default::default(),
+        )
+        .unwrap(),
+        crate::cbor::writer::Options {
+            mode: crate::cbor::writer::Mode::Array,
+        },
+    )
+    .unwrap();
+
+    // A record with a byte string, as produced by other encoders
+    data.extend_from_slice(&[0xa1, 0x63, b'b', b'i', b'n', 0x42, 0xca, 0xfe]);
+
+    let mut reader = Reader::new(data, Default::default()).unwrap();
+
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 1, "ratio": 1.0, "name": "a"})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 2, "ratio": 0.5, "name": null})
+    );
+
+    let mut expected = Object::new();
+    expected.insert("bin".into(), Value::Binary(vec![0xca, 0xfe]));
+    assert_eq!(reader.next().unwrap().unwrap(), Value::Object(expected));
+    assert!(reader.next().unwrap().is_none());
+}
+
+#[test]
+fn cbor_integers_and_tags() {
+    let check = |value: CborValue, expected: Value| {
+        let mut data = vec![];
+        ciborium::ser::into_writer(&value, &mut data).unwrap();
+        let mut reader = Reader::new(data, Default::default()).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), expected);
+    };
+    let tag = |tag: u64, value: CborValue| CborValue::Tag(tag, Box::new(value));
+    let string = |s: &'static str| Value::String(s.into());
+
+    check(
+        CborValue::Integer(u64::MAX.into()),
+        string("18446744073709551615"),
+    );
+    let mut bytes = vec![0; 17];
+    bytes[0] = 1;
+    check(
+        tag(2, CborValue::Bytes(bytes.clone())),
+        string("340282366920938463463374607431768211456"),
+    );
+    check(
+        tag(3, CborValue::Bytes(bytes)),
+        string("-340282366920938463463374607431768211457"),
+    );
+    // Small enough for an integer
+    check(tag(3, CborValue::Bytes(vec![0xff])), Value::Int(-256));
+    check(
+        tag(0, CborValue::Text("2020-09-13T12:26:40Z".into())),
+        string("2020-09-13T12:26:40Z"),
+    );
+    check(
+        tag(37, CborValue::Bytes(vec![1])),
+        Value::Object(
+            [
+                (Cow::Borrowed("tag"), Value::Int(37)),
+                (Cow::Borrowed("value"), Value::Binary(vec![1])),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    );
+}
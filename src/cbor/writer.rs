@@ -0,0 +1,60 @@
+use crate::reader::ValueReader;
+use crate::{Error, Result};
+
+use std::io::Write;
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Mode {
+    #[default]
+    #[serde(rename = "stream")]
+    Stream,
+    /// A single indefinite-length array holding every record
+    #[serde(rename = "array")]
+    Array,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+pub fn write<W: Write, R: ValueReader>(mut inner: W, mut reader: R, opts: Options) -> Result<()> {
+    if let Mode::Array = opts.mode {
+        inner.write_all(&[0x9f])?;
+    }
+
+    while let Some(row) = reader.next()? {
+        ciborium::ser::into_writer(&row, &mut inner).map_err(Error::output)?;
+    }
+
+    if let Mode::Array = opts.mode {
+        inner.write_all(&[0xff])?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cbor_write() {
+    let input = r#"{"a":1,"data":{"$binary":{"base64":"yv4=","subType":"00"}}}"#;
+    let write_with = |mode: Mode| {
+        let opts = crate::json::reader::Options {
+            extended_json: true,
+            ..Default::default()
+        };
+        let reader = crate::json::reader::RecordReader::new(input.as_bytes(), opts).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, Options { mode }).unwrap();
+        out
+    };
+
+    let record = [
+        0xa2, 0x61, b'a', 0x01, 0x64, b'd', b'a', b't', b'a', 0x42, 0xca, 0xfe,
+    ];
+    assert_eq!(write_with(Mode::Stream), record);
+    assert_eq!(
+        write_with(Mode::Array),
+        [&[0x9f][..], &record, &[0xff]].concat()
+    );
+}
@@ -1,116 +1,227 @@
 use crate::{
-    io::LineLengthLimiter,
+    csv::separator::{Lines, Separator, SeparatorReader, UNIT_SEPARATOR},
+    io::LengthLimiter,
     reader::{Object, Value, ValueReader},
-    Result, Schema,
+    Error, Result, Schema,
 };
 
 use csv::ByteRecord;
 
 use encoding_rs_io::DecodeReaderBytes;
-use serde::de::Deserializer;
-use serde::Deserialize;
 use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::io::{Read, Seek};
 
-#[derive(Debug)]
-pub struct Encoding(pub &'static encoding_rs::Encoding);
+pub use crate::io::Encoding;
 
-impl<'de> Deserialize<'de> for Encoding {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let str = String::deserialize(deserializer)?;
-        encoding_rs::Encoding::for_label_no_replacement(str.as_bytes())
-            .ok_or_else(|| serde::de::Error::custom(format!("{} is not a valid encoding", str)))
-            .map(Encoding)
-    }
-}
-
-type CsvReader<R> = csv::Reader<DecodeReaderBytes<LineLengthLimiter<R>, Vec<u8>>>;
+type CsvReader<R> = csv::Reader<SeparatorReader<DecodeReaderBytes<LengthLimiter<R>, Vec<u8>>>>;
 
 pub struct Reader<R> {
-    start_pos: csv::Position,
+    /// Offset of the input after the BOM and the skipped lines
+    start_offset: u64,
+    /// Records before the first row once the preamble is dropped, the header
+    skip_records: usize,
     headers: Vec<String>,
     reader: CsvReader<R>,
     buffer: ByteRecord,
+    /// Records read ahead to leave out the footer
+    lookahead: VecDeque<ByteRecord>,
+    spare: Option<ByteRecord>,
+    skip_footer: usize,
+    stop_at_blank_line: bool,
+    done: bool,
+    dialect: Dialect,
+    /// Error of the last reset, returned by the next read
+    error: Option<Error>,
 }
 
 impl<R: Read + Seek + Send + Sync + 'static> Reader<R> {
     pub fn new(mut inner_reader: R, options: Options) -> Result<Self> {
-        let mut bom_buffer = [0u8; 3];
-
-        let read_bom = inner_reader.read(&mut bom_buffer)?;
-
-        let (maybe_bom, skip_bom) = match encoding_rs::Encoding::for_bom(&bom_buffer[0..read_bom]) {
-            Some((e, s)) => (Some(Encoding(e)), s as u64),
-            None => (None, 0),
-        };
-
-        inner_reader.seek(std::io::SeekFrom::Start(skip_bom))?;
+        if let Some(separator) = &options.separator {
+            separator.validate()?;
+        }
 
-        let encoding = match options.encoding {
-            Some(encoding) => encoding,
-            None => {
-                let encoding = maybe_bom
-                    .map(Ok)
-                    .unwrap_or_else(|| infer_encoding(&mut inner_reader))?;
-                inner_reader.seek(std::io::SeekFrom::Start(skip_bom))?;
-                encoding
+        let encoding = detect_encoding(&mut inner_reader, options.encoding)?;
+        skip_lines(&mut inner_reader, &encoding, options.skip_rows)?;
+        let start_offset = inner_reader.stream_position()?;
+
+        let dialect = if options.sniff {
+            // Sniffed once decoded as the delimiters may not be single bytes
+            let mut decoder = encoding_rs_io::DecodeReaderBytesBuilder::new()
+                .encoding(Some(encoding.0))
+                .build(&mut inner_reader);
+            let dialect = sniff(&mut decoder)?;
+            inner_reader.seek(std::io::SeekFrom::Start(start_offset))?;
+            dialect
+        } else {
+            Dialect {
+                delimiter: options.delimiter,
+                quote: options.quote,
+                has_headers: options.has_headers,
             }
         };
 
-        let line_limiter = LineLengthLimiter::new(inner_reader, 5 * 1000 * 1000);
+        let line_limiter = LengthLimiter::lines(inner_reader, options.max_line_length);
 
         let decoder = encoding_rs_io::DecodeReaderBytesBuilder::new()
             .encoding(Some(encoding.0))
             .build(line_limiter);
 
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(options.has_headers)
-            .flexible(true)
-            .delimiter(options.delimiter)
-            .from_reader(decoder);
+        let lines = Lines {
+            comment: options.comment,
+            terminator: options.terminator,
+            stop_at_blank_line: options.stop_at_blank_line,
+            skip_records: options.header_row,
+        };
+        let separated = SeparatorReader::new(
+            decoder,
+            options.separator.clone(),
+            dialect.quote,
+            options.escape,
+            options.double_quote,
+            lines,
+        );
+
+        // Headers are read as any other record as they may not be on the first line
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(!options.strict)
+            .delimiter(match options.separator {
+                Some(_) => UNIT_SEPARATOR,
+                None => dialect.delimiter,
+            })
+            .quote(dialect.quote)
+            .escape(options.escape)
+            .double_quote(options.double_quote)
+            .trim(match options.trim {
+                Trim::None => csv::Trim::None,
+                Trim::Headers => csv::Trim::None,
+                Trim::Fields => csv::Trim::Fields,
+                Trim::All => csv::Trim::All,
+            })
+            .terminator(match options.terminator {
+                Some(terminator) => csv::Terminator::Any(terminator),
+                None => csv::Terminator::CRLF,
+            })
+            .from_reader(separated);
+
+        let mut reader = Reader {
+            start_offset,
+            skip_records: 0,
+            headers: vec![],
+            buffer: ByteRecord::default(),
+            reader,
+            lookahead: VecDeque::new(),
+            spare: None,
+            skip_footer: options.skip_footer,
+            stop_at_blank_line: options.stop_at_blank_line,
+            done: false,
+            dialect,
+            error: None,
+        };
 
-        let mut start_pos = reader.position().clone();
+        reader.rewind()?;
+        reader.reader.read_byte_record(&mut reader.buffer)?;
 
-        let headers: Vec<String>;
+        reader.headers = if reader.dialect.has_headers {
+            reader.skip_records += 1;
 
-        if options.has_headers {
-            headers = reader
-                .headers()?
+            let trim = matches!(options.trim, Trim::Headers | Trim::All);
+            reader
+                .buffer
                 .iter()
-                .map(String::from)
-                .collect::<Vec<_>>();
-            start_pos = reader.position().clone();
+                .map(|header| {
+                    let header = String::from_utf8_lossy(header);
+                    if trim {
+                        header.trim().to_string()
+                    } else {
+                        header.into_owned()
+                    }
+                })
+                .collect::<Vec<_>>()
         } else {
-            headers = (0..reader.headers()?.len())
+            (0..reader.buffer.len())
                 .map(|i| format!("column_{}", i + 1))
-                .collect::<Vec<_>>();
-
-            reader.seek(start_pos.clone())?;
+                .collect::<Vec<_>>()
         };
 
-        Ok(Reader {
-            start_pos,
-            headers,
-            buffer: ByteRecord::default(),
-            reader,
-        })
+        reader.rewind()?;
+
+        Ok(reader)
+    }
+
+    /// The dialect the input is read with, either the one sniffed or the one
+    /// from the options.
+    pub fn dialect(&self) -> &Dialect {
+        &self.dialect
+    }
+
+    /// Goes back to the first row, reading again the records before it since
+    /// the positions of the CSV reader are in decoded bytes.
+    fn rewind(&mut self) -> Result<()> {
+        self.reader.seek_raw(
+            std::io::SeekFrom::Start(self.start_offset),
+            csv::Position::new(),
+        )?;
+
+        for _ in 0..self.skip_records {
+            self.reader.read_byte_record(&mut self.buffer)?;
+        }
+
+        self.lookahead.clear();
+        self.done = false;
+
+        Ok(())
+    }
+
+    /// Reads the next record, unless the rows stop there.
+    fn read_record(&mut self) -> Result<Option<ByteRecord>> {
+        let mut record = self.spare.take().unwrap_or_default();
+
+        if self.done || !self.reader.read_byte_record(&mut record)? {
+            self.done = true;
+            self.spare = Some(record);
+            return Ok(None);
+        }
+
+        // Blank lines end the input, see `SeparatorReader`, lines of empty
+        // fields are checked here
+        if self.stop_at_blank_line && record.iter().all(|f| f.is_empty()) {
+            self.done = true;
+            self.spare = Some(record);
+            return Ok(None);
+        }
+
+        Ok(Some(record))
     }
 }
 
 impl<R: Read + Seek + 'static + Send + Sync> ValueReader for Reader<R> {
     fn reset(&mut self) {
-        self.reader.seek(self.start_pos.clone()).unwrap()
+        self.error = self.rewind().err();
     }
 
     fn next(&mut self) -> Result<Option<Value<'_>>> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
         let mut row = Object::default();
 
-        if !self.reader.read_byte_record(&mut self.buffer)? {
+        while self.lookahead.len() <= self.skip_footer {
+            match self.read_record()? {
+                Some(record) => self.lookahead.push_back(record),
+                None => break,
+            }
+        }
+
+        // Whatever is left is the footer
+        if self.lookahead.len() <= self.skip_footer {
             Ok(None)
         } else {
+            let record = self.lookahead.pop_front().unwrap();
+            self.spare = Some(std::mem::replace(&mut self.buffer, record));
+
             row.reserve(self.headers.len());
 
             for (f, value) in self.headers.iter().zip(self.buffer.iter()) {
@@ -122,26 +233,283 @@ impl<R: Read + Seek + 'static + Send + Sync> ValueReader for Reader<R> {
     }
 }
 
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Trim {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "headers")]
+    Headers,
+    #[serde(rename = "fields")]
+    Fields,
+    #[serde(rename = "all")]
+    All,
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct Options {
     pub delimiter: u8,
+    /// Separates fields instead of `delimiter`, for the ones longer than a
+    /// byte or runs of whitespace
+    #[serde(default)]
+    pub separator: Option<Separator>,
     pub has_headers: bool,
     pub encoding: Option<Encoding>,
     pub schema: Option<Schema>,
+    #[serde(default = "default_quote")]
+    pub quote: u8,
+    /// Character escaping quotes inside quoted fields, usually `\`
+    #[serde(default)]
+    pub escape: Option<u8>,
+    /// Whether `""` inside quoted fields is an escaped quote
+    #[serde(default = "default_true")]
+    pub double_quote: bool,
+    /// Lines starting with this character are skipped
+    #[serde(default)]
+    pub comment: Option<u8>,
+    /// Strips the whitespace around headers and/or fields
+    #[serde(default)]
+    pub trim: Trim,
+    /// Errors on rows with a different number of fields than the header
+    #[serde(default)]
+    pub strict: bool,
+    /// Record terminator, any of `\r`, `\n` and `\r\n` when not set
+    #[serde(default)]
+    pub terminator: Option<u8>,
+    /// Detects the delimiter, quote and headers from a sample of the input
+    /// instead of taking them from these options, see [`sniff`]
+    #[serde(default)]
+    pub sniff: bool,
+    /// Lines skipped before reading anything, like the title of an export
+    #[serde(default)]
+    pub skip_rows: usize,
+    /// Index of the header among the records after `skip_rows`, the records
+    /// before it are skipped
+    #[serde(default)]
+    pub header_row: usize,
+    /// Records left out at the end, like totals
+    #[serde(default)]
+    pub skip_footer: usize,
+    /// Stops at the first blank line after the header, or line of empty fields
+    #[serde(default)]
+    pub stop_at_blank_line: bool,
+    /// Longest line allowed in bytes, `null` for no limit
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: Option<usize>,
+}
+
+fn default_quote() -> u8 {
+    b'"'
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_line_length() -> Option<usize> {
+    Some(5 * 1000 * 1000)
 }
 
 impl Default for Options {
     fn default() -> Self {
         Self {
             delimiter: b',',
+            separator: None,
             schema: None,
             has_headers: true,
             encoding: None,
+            quote: default_quote(),
+            escape: None,
+            double_quote: true,
+            comment: None,
+            trim: Trim::default(),
+            strict: false,
+            terminator: None,
+            sniff: false,
+            skip_rows: 0,
+            header_row: 0,
+            skip_footer: 0,
+            stop_at_blank_line: false,
+            max_line_length: default_max_line_length(),
+        }
+    }
+}
+
+/// Returns the encoding of the input, either the one given, the one from the
+/// BOM or a guess from its contents, leaving the reader positioned after the BOM.
+pub fn detect_encoding<R: Read + Seek>(
+    inner_reader: &mut R,
+    encoding: Option<Encoding>,
+) -> Result<Encoding> {
+    let mut bom_buffer = [0u8; 3];
+
+    let read_bom = inner_reader.read(&mut bom_buffer)?;
+
+    let (maybe_bom, skip_bom) = match encoding_rs::Encoding::for_bom(&bom_buffer[0..read_bom]) {
+        Some((e, s)) => (Some(Encoding(e)), s as u64),
+        None => (None, 0),
+    };
+
+    inner_reader.seek(std::io::SeekFrom::Start(skip_bom))?;
+
+    let encoding = match encoding {
+        Some(encoding) => encoding,
+        None => {
+            let encoding = maybe_bom
+                .map(Ok)
+                .unwrap_or_else(|| infer_encoding(inner_reader))?;
+            inner_reader.seek(std::io::SeekFrom::Start(skip_bom))?;
+            encoding
+        }
+    };
+
+    Ok(encoding)
+}
+
+/// Dialect of a CSV input.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Dialect {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub has_headers: bool,
+}
+
+const SNIFF_DELIMITERS: &[u8] = b",;\t|";
+const SNIFF_QUOTES: &[u8] = b"\"'";
+const SNIFF_LIMIT_SIZE: u64 = 64 * 1024;
+const SNIFF_LIMIT_ROWS: usize = 100;
+
+fn sample_records(sample: &[u8], delimiter: u8, quote: u8) -> Vec<ByteRecord> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .quote(quote)
+        .from_reader(sample)
+        .into_byte_records()
+        .take(SNIFF_LIMIT_ROWS)
+        .map_while(|record| record.ok())
+        .collect()
+}
+
+/// How well records fit a dialect: whether they have several fields, the
+/// number of records having the most common number of fields, and that number.
+type Score = (bool, usize, usize);
+
+fn score(records: &[ByteRecord]) -> Score {
+    let mut counts = std::collections::HashMap::<usize, usize>::new();
+    for record in records {
+        *counts.entry(record.len()).or_default() += 1;
+    }
+
+    let (width, count) = counts
+        .into_iter()
+        .max_by_key(|&(width, count)| (count, width))
+        .unwrap_or((0, 0));
+
+    (width > 1, count, width)
+}
+
+fn is_numeric(field: &[u8]) -> bool {
+    lexical_core::parse::<f64>(field).is_ok()
+}
+
+/// Guesses whether the first record is a header, it is when it has text in
+/// the columns that are otherwise numeric.
+fn sniff_headers(records: &[ByteRecord]) -> bool {
+    let Some((first, rest)) = records.split_first() else {
+        return true;
+    };
+
+    let mut votes = 0;
+
+    for (i, header) in first.iter().enumerate() {
+        let mut column = rest
+            .iter()
+            .filter_map(|record| record.get(i))
+            .filter(|field| !field.is_empty())
+            .peekable();
+
+        if column.peek().is_some() && column.all(is_numeric) {
+            votes += if is_numeric(header) { -1 } else { 1 };
+        }
+    }
+
+    votes >= 0
+}
+
+/// Detects the delimiter, quote character and header presence from a sample
+/// of the input, trying each candidate dialect and keeping the one splitting
+/// the most records into the same number of fields.
+pub fn sniff<R: Read>(inner_reader: &mut R) -> Result<Dialect> {
+    let mut sample = vec![];
+    inner_reader
+        .take(SNIFF_LIMIT_SIZE)
+        .read_to_end(&mut sample)?;
+
+    // The last line is likely cut
+    if sample.len() as u64 == SNIFF_LIMIT_SIZE {
+        if let Some(end) = sample.iter().rposition(|&b| b == b'\n') {
+            sample.truncate(end + 1);
+        }
+    }
+
+    let mut best: Option<(Score, Dialect, Vec<ByteRecord>)> = None;
+
+    for &quote in SNIFF_QUOTES {
+        for &delimiter in SNIFF_DELIMITERS {
+            let records = sample_records(&sample, delimiter, quote);
+            let score = score(&records);
+
+            let better = match &best {
+                Some((best, ..)) => score > *best,
+                None => true,
+            };
+
+            if better {
+                let dialect = Dialect {
+                    delimiter,
+                    quote,
+                    has_headers: true,
+                };
+                best = Some((score, dialect, records));
+            }
+        }
+    }
+
+    let (_, mut dialect, records) = best.ok_or("No CSV dialect found")?;
+    dialect.has_headers = sniff_headers(&records);
+
+    Ok(dialect)
+}
+
+/// Skips `count` lines of the raw input, whatever they contain.
+fn skip_lines<R: Read>(inner_reader: &mut R, encoding: &Encoding, count: usize) -> Result<()> {
+    let newline: &[u8] = if encoding.0 == encoding_rs::UTF_16LE {
+        b"\n\0"
+    } else if encoding.0 == encoding_rs::UTF_16BE {
+        b"\0\n"
+    } else {
+        b"\n"
+    };
+
+    let mut unit = [0u8; 2];
+    let unit = &mut unit[..newline.len()];
+    let mut skipped = 0;
+
+    while skipped < count {
+        match inner_reader.read_exact(unit) {
+            Ok(()) if unit == newline => skipped += 1,
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
         }
     }
+
+    Ok(())
 }
 
-fn infer_encoding<R: Read>(inner_reader: &mut R) -> Result<Encoding> {
+pub fn infer_encoding<R: Read>(inner_reader: &mut R) -> Result<Encoding> {
     use chardetng::EncodingDetector;
     let encoding_limit_size = 500 * 1000;
     let mut buffer = [0; 8192];
@@ -211,3 +579,322 @@ fn latin1() {
     //     Row::from(vec![Value::Int(1), Value::String("Jêroño".to_string())])
     // );
 }
+
+#[test]
+fn csv_dialect() {
+    let read = |input: &'static str, options: Options| {
+        let mut reader = Reader::new(std::io::Cursor::new(input), options)?;
+        let mut rows = vec![];
+        while let Some(row) = reader.next()? {
+            rows.push(serde_json::to_string(&row).unwrap());
+        }
+        Result::Ok(rows)
+    };
+
+    assert_eq!(
+        read(
+            "# exported\n id ; text \n1;'it\\'s' \n2;'a;b'\n",
+            Options {
+                delimiter: b';',
+                quote: b'\'',
+                escape: Some(b'\\'),
+                comment: Some(b'#'),
+                trim: Trim::All,
+                ..Default::default()
+            }
+        )
+        .unwrap(),
+        vec![r#"{"id":1,"text":"it's"}"#, r#"{"id":2,"text":"a;b"}"#]
+    );
+
+    assert_eq!(
+        read(
+            "a,b|1,2|3,4|",
+            Options {
+                terminator: Some(b'|'),
+                ..Default::default()
+            }
+        )
+        .unwrap(),
+        vec![r#"{"a":1,"b":2}"#, r#"{"a":3,"b":4}"#]
+    );
+
+    let strict = Options {
+        strict: true,
+        ..Default::default()
+    };
+    assert!(read("a,b\n1,2\n3\n", strict).is_err());
+
+    // The preamble is dropped before the field counts are compared
+    let strict = Options {
+        strict: true,
+        header_row: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        read("\"Report\nQ1\",x,y\n\ndate,amount\n01/01,10\n", strict).unwrap(),
+        vec![r#"{"date":"01/01","amount":10}"#]
+    );
+
+    let read_quotes = |double_quote: bool| {
+        read(
+            "\"x\"\"||y\"||z\n",
+            Options {
+                has_headers: false,
+                separator: Some(Separator::String("||".into())),
+                double_quote,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        read_quotes(true),
+        vec![r#"{"column_1":"x\"||y","column_2":"z"}"#]
+    );
+    assert_eq!(
+        read_quotes(false),
+        vec![r#"{"column_1":"x\"","column_2":"y\"","column_3":"z"}"#]
+    );
+}
+
+#[test]
+fn csv_sniff() {
+    let sniff = |input: &str| sniff(&mut input.as_bytes()).unwrap();
+
+    assert_eq!(
+        sniff("name;price;note\nfoo;1,5;'a'\nbar;2;\"x;y\"\n"),
+        Dialect {
+            delimiter: b';',
+            quote: b'"',
+            has_headers: true,
+        }
+    );
+    assert_eq!(
+        sniff("1\tfoo, bar\n2\tbaz\n3\tqux\n"),
+        Dialect {
+            delimiter: b'\t',
+            quote: b'"',
+            has_headers: false,
+        }
+    );
+    assert_eq!(
+        sniff("id|name\n1|'a|b'\n2|'c'\n"),
+        Dialect {
+            delimiter: b'|',
+            quote: b'\'',
+            has_headers: true,
+        }
+    );
+
+    let options = Options {
+        delimiter: b',',
+        sniff: true,
+        ..Default::default()
+    };
+    let mut reader = Reader::new(std::io::Cursor::new("a;b\n1;2\n"), options).unwrap();
+    assert_eq!(reader.dialect().delimiter, b';');
+    assert_eq!(
+        serde_json::to_string(&reader.next().unwrap().unwrap()).unwrap(),
+        r#"{"a":1,"b":2}"#
+    );
+
+    let mut utf16 = vec![0xff, 0xfe];
+    for unit in "1;2\n3;4\n".encode_utf16() {
+        utf16.extend_from_slice(&unit.to_le_bytes());
+    }
+    let options = Options {
+        sniff: true,
+        ..Default::default()
+    };
+    let mut reader = Reader::new(std::io::Cursor::new(utf16), options).unwrap();
+    assert_eq!(
+        reader.dialect(),
+        &Dialect {
+            delimiter: b';',
+            quote: b'"',
+            has_headers: false,
+        }
+    );
+    assert_eq!(
+        serde_json::to_string(&reader.next().unwrap().unwrap()).unwrap(),
+        r#"{"column_1":1,"column_2":2}"#
+    );
+}
+
+#[test]
+fn csv_preamble() {
+    let input = "Account statement\n\"Period: 2022, \"\"Q1\"\"\n\
+                 generated,today\n\
+                 date,amount\n\
+                 01/01,10\n\
+                 02/01,20\n\
+                 \n\
+                 Total,30\n";
+
+    let options = Options {
+        skip_rows: 2,
+        header_row: 1,
+        stop_at_blank_line: true,
+        ..Default::default()
+    };
+    let mut reader = Reader::new(std::io::Cursor::new(input), options).unwrap();
+
+    for _ in 0..2 {
+        let mut rows = vec![];
+        while let Some(row) = reader.next().unwrap() {
+            rows.push(serde_json::to_string(&row).unwrap());
+        }
+        assert_eq!(
+            rows,
+            vec![
+                r#"{"date":"01/01","amount":10}"#,
+                r#"{"date":"02/01","amount":20}"#
+            ]
+        );
+        reader.reset();
+    }
+
+    let options = Options {
+        has_headers: false,
+        skip_footer: 2,
+        ..Default::default()
+    };
+    let mut reader = Reader::new(
+        std::io::Cursor::new("\u{feff}1,a\n2,b\nTotal,2\nEnd\n"),
+        options,
+    )
+    .unwrap();
+    reader.infer_schema().unwrap();
+    let mut rows = vec![];
+    while let Some(row) = reader.next().unwrap() {
+        rows.push(serde_json::to_string(&row).unwrap());
+    }
+    assert_eq!(
+        rows,
+        vec![
+            r#"{"column_1":1,"column_2":"a"}"#,
+            r#"{"column_1":2,"column_2":"b"}"#
+        ]
+    );
+}
+
+#[test]
+fn csv_line_length() {
+    let read = |max_line_length: Option<usize>| {
+        let options = Options {
+            max_line_length,
+            ..Default::default()
+        };
+        let mut reader = Reader::new(std::io::Cursor::new("a,b\n1,2\n12345,6\n"), options)?;
+        while reader.next()?.is_some() {}
+        Result::Ok(())
+    };
+
+    assert!(read(Some(7)).is_ok());
+    assert!(read(None).is_ok());
+    assert_eq!(
+        read(Some(6)).unwrap_err().to_string(),
+        "Line length exceeds 6 bytes"
+    );
+}
+
+#[test]
+fn csv_separator() {
+    let read = |input: &'static str, separator: Separator| {
+        let options = Options {
+            separator: Some(separator),
+            ..Default::default()
+        };
+        let mut reader = Reader::new(std::io::Cursor::new(input), options).unwrap();
+        let mut rows = vec![];
+        while let Some(row) = reader.next().unwrap() {
+            rows.push(serde_json::to_string(&row).unwrap());
+        }
+        rows
+    };
+
+    assert_eq!(
+        read(
+            "a~|~b\n1~|~\"x~|~y\"\n~~|~|~\n",
+            Separator::String("~|~".into())
+        ),
+        vec![r#"{"a":1,"b":"x~|~y"}"#, r#"{"a":"~","b":"|~"}"#]
+    );
+    assert_eq!(
+        read(
+            "  id  name\tnote \n1 \"a b\"   x\n2\t\t\"\"  y  \n",
+            Separator::Whitespace
+        ),
+        vec![
+            r#"{"id":1,"name":"a b","note":"x"}"#,
+            r#"{"id":2,"name":"","note":"y"}"#
+        ]
+    );
+
+    // Unit separators are only allowed in quoted fields
+    assert_eq!(
+        read("a||b\n\"\x1f\"||x\n", Separator::String("||".into())),
+        vec![r#"{"a":"\u001f","b":"x"}"#]
+    );
+    let options = Options {
+        separator: Some(Separator::String("||".into())),
+        ..Default::default()
+    };
+    assert!(Reader::new(std::io::Cursor::new("a||b\nx\x1fy||z\n"), options).is_err());
+
+    let options = Options {
+        separator: Some(Separator::String("".into())),
+        ..Default::default()
+    };
+    assert!(Reader::new(std::io::Cursor::new("a\n"), options).is_err());
+}
+
+#[test]
+fn csv_blank_line() {
+    let read = |input: &str, options: Options| {
+        let options = Options {
+            stop_at_blank_line: true,
+            ..options
+        };
+        let mut reader = Reader::new(std::io::Cursor::new(input.to_string()), options).unwrap();
+        let mut rows = vec![];
+        while let Some(row) = reader.next().unwrap() {
+            rows.push(serde_json::to_string(&row).unwrap());
+        }
+        rows
+    };
+
+    let rows = vec![r#"{"a":1,"b":2}"#, r#"{"a":3,"b":4}"#];
+
+    assert_eq!(
+        read(
+            "\na,b\n1,2\n# note\n3,4\n\n5,6\n",
+            Options {
+                comment: Some(b'#'),
+                ..Default::default()
+            }
+        ),
+        rows
+    );
+    assert_eq!(
+        read(
+            "a,b;1,2;# note;3,4;;5,6;",
+            Options {
+                comment: Some(b'#'),
+                terminator: Some(b';'),
+                ..Default::default()
+            }
+        ),
+        rows
+    );
+    assert_eq!(read("a,b\r1,2\r3,4\r\r5,6\r", Default::default()), rows);
+    assert_eq!(
+        read("a,b\r\n1,2\r\n3,4\r\n\r\n5,6", Default::default()),
+        rows
+    );
+    assert_eq!(
+        read("a,b\n\"x\n\ny\",2\n\n5,6\n", Default::default()),
+        vec![r#"{"a":"x\n\ny","b":2}"#]
+    );
+}
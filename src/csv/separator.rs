@@ -0,0 +1,443 @@
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// Byte the separators are replaced with before parsing, the ASCII unit
+/// separator is unlikely to be found in the data.
+pub const UNIT_SEPARATOR: u8 = 0x1f;
+
+/// Field separator the CSV parser can't handle as a single byte delimiter.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub enum Separator {
+    /// Any string, like `||` or `~|~`
+    #[serde(rename = "string")]
+    String(String),
+    /// Runs of spaces and tabs, ignoring them at the start and end of lines
+    #[serde(rename = "whitespace")]
+    Whitespace,
+}
+
+impl Separator {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Separator::String(separator) if separator.is_empty() => {
+                Err(Error::CustomError("The separator can't be empty".into()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Line handling of [`SeparatorReader`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Lines {
+    /// Lines starting with this character are comments
+    pub comment: Option<u8>,
+    /// Record terminator, any of `\r`, `\n` and `\r\n` when not set
+    pub terminator: Option<u8>,
+    /// Ends the input at the first blank line after some content
+    pub stop_at_blank_line: bool,
+    /// Records dropped at the start of the input, like a preamble before the
+    /// header, so the CSV reader only counts the fields of the others
+    pub skip_records: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    FieldStart,
+    Unquoted,
+    Quoted,
+    /// After an escape character inside quotes
+    Escaped,
+    /// After a quote inside quotes, either the end of the field or an
+    /// escaped quote
+    QuoteInQuoted,
+    Comment,
+}
+
+/// Replaces the separators outside of quoted fields with [`UNIT_SEPARATOR`],
+/// drops the comment lines and the skipped records and ends the input at the
+/// first blank line when asked to, passing the input through untouched when
+/// there is nothing to do.
+///
+/// Comments are dropped here as the CSV reader ends them at `\n` whatever the
+/// terminator.
+///
+/// Unit separators already in the input outside of quoted fields are an error
+/// when there is a separator, they would split the fields.
+pub struct SeparatorReader<R> {
+    inner: R,
+    separator: Option<Separator>,
+    quote: u8,
+    escape: Option<u8>,
+    /// Whether `""` inside quoted fields is an escaped quote
+    double_quote: bool,
+    lines: Lines,
+    state: State,
+    /// Records dropped so far
+    skipped: usize,
+    line_start: bool,
+    previous: u8,
+    /// Whether anything but line terminators was read
+    has_content: bool,
+    /// A blank line was found
+    ended: bool,
+    /// A unit separator was found outside of quoted fields
+    invalid: bool,
+    /// Start of a separator, or whitespace not known to be a separator yet
+    pending: Vec<u8>,
+    output: VecDeque<u8>,
+    chunk: Vec<u8>,
+}
+
+impl<R: Read> SeparatorReader<R> {
+    pub fn new(
+        inner: R,
+        separator: Option<Separator>,
+        quote: u8,
+        escape: Option<u8>,
+        double_quote: bool,
+        lines: Lines,
+    ) -> Self {
+        SeparatorReader {
+            inner,
+            separator,
+            quote,
+            escape,
+            double_quote,
+            lines,
+            state: State::FieldStart,
+            skipped: 0,
+            line_start: true,
+            previous: 0,
+            has_content: false,
+            ended: false,
+            invalid: false,
+            pending: vec![],
+            output: VecDeque::new(),
+            chunk: vec![0; 8192],
+        }
+    }
+
+    fn is_newline(&self, b: u8) -> bool {
+        match self.lines.terminator {
+            Some(terminator) => b == terminator,
+            None => b == b'\n' || b == b'\r',
+        }
+    }
+
+    fn passes_through(&self) -> bool {
+        self.separator.is_none()
+            && self.lines.comment.is_none()
+            && !self.lines.stop_at_blank_line
+            && self.lines.skip_records == 0
+    }
+
+    fn emit(&mut self, b: u8) {
+        if self.skipped >= self.lines.skip_records {
+            self.output.push_back(b);
+        }
+    }
+
+    fn field_byte(&mut self, b: u8) {
+        let newline = self.is_newline(b);
+
+        // Blank lines aren't records for the CSV reader
+        if newline && !self.line_start && self.skipped < self.lines.skip_records {
+            self.skipped += 1;
+        } else {
+            self.emit(b);
+        }
+
+        self.line_start = newline;
+        self.has_content |= !newline;
+
+        self.state = match self.state {
+            _ if newline => State::FieldStart,
+            State::FieldStart if b == self.quote => State::Quoted,
+            State::QuoteInQuoted if b == self.quote && self.double_quote => State::Quoted,
+            _ => State::Unquoted,
+        };
+    }
+
+    fn separator_found(&mut self) {
+        self.emit(UNIT_SEPARATOR);
+        self.state = State::FieldStart;
+    }
+
+    fn feed(&mut self, b: u8) {
+        match self.state {
+            State::Quoted => {
+                self.emit(b);
+                if Some(b) == self.escape {
+                    self.state = State::Escaped;
+                } else if b == self.quote {
+                    self.state = State::QuoteInQuoted;
+                }
+                return;
+            }
+            State::Escaped => {
+                self.emit(b);
+                self.state = State::Quoted;
+                return;
+            }
+            State::Comment => {
+                if self.is_newline(b) {
+                    self.state = State::FieldStart;
+                    self.line_start = true;
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        if self.line_start && self.pending.is_empty() {
+            if Some(b) == self.lines.comment {
+                self.state = State::Comment;
+                self.line_start = false;
+                return;
+            }
+
+            // The `\n` of `\r\n` doesn't make a blank line
+            let crlf = b == b'\n' && self.previous == b'\r' && self.lines.terminator.is_none();
+            if self.lines.stop_at_blank_line && self.has_content && self.is_newline(b) && !crlf {
+                self.ended = true;
+                return;
+            }
+        }
+
+        if b == UNIT_SEPARATOR && self.separator.is_some() {
+            self.invalid = true;
+            return;
+        }
+
+        match &self.separator {
+            Some(Separator::String(separator)) => {
+                self.pending.push(b);
+
+                if self.pending == separator.as_bytes() {
+                    self.pending.clear();
+                    self.separator_found();
+                } else if !separator.as_bytes().starts_with(&self.pending) {
+                    let pending = std::mem::take(&mut self.pending);
+                    self.field_byte(pending[0]);
+                    for &b in &pending[1..] {
+                        self.feed(b);
+                    }
+                }
+            }
+            Some(Separator::Whitespace) => {
+                if b == b' ' || b == b'\t' {
+                    if !self.line_start {
+                        self.pending.push(b);
+                    }
+                    return;
+                }
+
+                // Trailing whitespace isn't a separator
+                let newline = b == b'\n' || b == b'\r';
+                if !std::mem::take(&mut self.pending).is_empty() && !newline {
+                    self.separator_found();
+                }
+
+                self.field_byte(b);
+            }
+            None => self.field_byte(b),
+        }
+    }
+
+    /// Flushes what's left at the end of the input.
+    fn finish(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if let Some(Separator::String(_)) = self.separator {
+            for b in pending {
+                self.field_byte(b);
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for SeparatorReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.passes_through() {
+            return self.inner.read(buf);
+        }
+
+        while self.output.is_empty() && !self.ended {
+            let bytes_read = self.inner.read(&mut self.chunk)?;
+
+            if bytes_read == 0 {
+                self.finish();
+                break;
+            }
+
+            for i in 0..bytes_read {
+                let b = self.chunk[i];
+                self.feed(b);
+                self.previous = b;
+
+                if self.invalid {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Unit separator (0x1F) found outside of quotes, \
+                         it can't be read along with a separator",
+                    ));
+                }
+
+                if self.ended {
+                    self.finish();
+                    break;
+                }
+            }
+        }
+
+        let len = buf.len().min(self.output.len());
+        for (dst, src) in buf.iter_mut().zip(self.output.drain(..len)) {
+            *dst = src;
+        }
+
+        Ok(len)
+    }
+}
+
+impl<R: Seek> Seek for SeparatorReader<R> {
+    fn seek(&mut self, from: SeekFrom) -> std::io::Result<u64> {
+        self.state = State::FieldStart;
+        self.skipped = 0;
+        self.line_start = true;
+        self.previous = 0;
+        self.has_content = false;
+        self.ended = false;
+        self.invalid = false;
+        self.pending.clear();
+        self.output.clear();
+        self.inner.seek(from)
+    }
+}
+
+/// How fields are quoted by [`SeparatorWriter`].
+pub struct Quoting {
+    pub quote_style: csv::QuoteStyle,
+    pub quote: u8,
+    pub escape: Option<u8>,
+}
+
+/// Writes records with a [`Separator`], or a delimiter when the CSV writer
+/// can't quote fields as asked, quoting the fields containing it.
+pub struct SeparatorWriter<W> {
+    inner: W,
+    separator: Vec<u8>,
+    /// Whether the separator stands for any run of whitespace
+    whitespace: bool,
+    quoting: Quoting,
+    terminator: &'static [u8],
+    record_start: bool,
+}
+
+impl<W: Write> SeparatorWriter<W> {
+    pub fn new(
+        inner: W,
+        separator: Separator,
+        quoting: Quoting,
+        terminator: &'static [u8],
+    ) -> Self {
+        let (separator, whitespace) = match separator {
+            Separator::String(separator) => (separator.into_bytes(), false),
+            Separator::Whitespace => (b" ".to_vec(), true),
+        };
+
+        SeparatorWriter {
+            inner,
+            separator,
+            whitespace,
+            quoting,
+            terminator,
+            record_start: true,
+        }
+    }
+
+    pub fn with_delimiter(
+        inner: W,
+        delimiter: u8,
+        quoting: Quoting,
+        terminator: &'static [u8],
+    ) -> Self {
+        SeparatorWriter {
+            inner,
+            separator: vec![delimiter],
+            whitespace: false,
+            quoting,
+            terminator,
+            record_start: true,
+        }
+    }
+
+    fn needs_quotes(&self, field: &[u8], quote_style: csv::QuoteStyle) -> bool {
+        let special = |b: &u8| *b == self.quoting.quote || *b == b'\n' || *b == b'\r';
+
+        let contains_separator = if self.whitespace {
+            // Empty fields would vanish between the whitespace
+            field.is_empty() || field.iter().any(|b| *b == b' ' || *b == b'\t')
+        } else {
+            field
+                .windows(self.separator.len())
+                .any(|w| w == self.separator)
+        };
+
+        match quote_style {
+            csv::QuoteStyle::Always => true,
+            csv::QuoteStyle::Never => false,
+            csv::QuoteStyle::NonNumeric if lexical_core::parse::<f64>(field).is_err() => true,
+            _ => contains_separator || field.iter().any(special),
+        }
+    }
+
+    pub fn write_field(&mut self, field: &[u8]) -> std::io::Result<()> {
+        let quoted = self.needs_quotes(field, self.quoting.quote_style);
+        self.write(field, quoted)
+    }
+
+    /// Writes the string standing for nulls, only quoted when it has to be so
+    /// it can't be taken for a string.
+    pub fn write_null(&mut self, null: &[u8]) -> std::io::Result<()> {
+        let quoted = self.needs_quotes(null, csv::QuoteStyle::Necessary);
+        self.write(null, quoted)
+    }
+
+    fn write(&mut self, field: &[u8], quoted: bool) -> std::io::Result<()> {
+        if !self.record_start {
+            self.inner.write_all(&self.separator)?;
+        }
+        self.record_start = false;
+
+        if !quoted {
+            return self.inner.write_all(field);
+        }
+
+        let quote = self.quoting.quote;
+        self.inner.write_all(&[quote])?;
+
+        for part in field.split_inclusive(|b| *b == quote) {
+            match part.split_last() {
+                Some((&last, rest)) if last == quote => {
+                    self.inner.write_all(rest)?;
+                    self.inner
+                        .write_all(&[self.quoting.escape.unwrap_or(quote), quote])?;
+                }
+                _ => self.inner.write_all(part)?,
+            }
+        }
+
+        self.inner.write_all(&[quote])
+    }
+
+    pub fn write_record_end(&mut self) -> std::io::Result<()> {
+        self.record_start = true;
+        self.inner.write_all(self.terminator)
+    }
+
+    pub fn into_inner(mut self) -> std::io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
@@ -1,10 +1,74 @@
-use crate::reader::ValueReader;
-use crate::{Result, Value};
+use crate::csv::separator::{Quoting, Separator, SeparatorWriter};
+use crate::io::{Encoding, EncodingWriter, Unmappable};
+use crate::normalize;
+use crate::reader::{RowIterator, ValueReader};
+use crate::{Error, Result, Value};
 use std::io::Write;
 
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum QuoteStyle {
+    #[serde(rename = "always")]
+    Always,
+    #[default]
+    #[serde(rename = "necessary")]
+    Necessary,
+    #[serde(rename = "non_numeric")]
+    NonNumeric,
+    #[serde(rename = "never")]
+    Never,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum LineTerminator {
+    #[default]
+    #[serde(rename = "lf")]
+    Lf,
+    #[serde(rename = "crlf")]
+    Crlf,
+}
+
 #[derive(Debug, serde::Deserialize)]
+#[serde(default)]
 pub struct Options {
     pub delimiter: u8,
+    /// Written between fields instead of `delimiter`
+    pub separator: Option<Separator>,
+    pub quote_style: QuoteStyle,
+    pub quote: u8,
+    /// Escapes quotes inside fields with this character instead of doubling them
+    pub escape: Option<u8>,
+    pub line_terminator: LineTerminator,
+    pub has_headers: bool,
+    /// Written for nulls, which are otherwise empty like empty strings
+    pub null_string: String,
+    /// Starts the output with a byte order mark, only possible in UTF-8 and
+    /// UTF-16
+    pub bom: bool,
+    /// Output encoding, UTF-8 when not set
+    pub encoding: Option<Encoding>,
+    pub unmappable: Unmappable,
+    /// Moves arrays of objects to child tables, one file per table, see
+    /// [`write_tables`]
+    pub normalize: Option<normalize::Options>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            separator: None,
+            quote_style: QuoteStyle::default(),
+            quote: b'"',
+            escape: None,
+            line_terminator: LineTerminator::default(),
+            has_headers: true,
+            null_string: String::new(),
+            bom: false,
+            encoding: None,
+            unmappable: Unmappable::default(),
+            normalize: None,
+        }
+    }
 }
 
 pub fn write_value(value: &Value, buf: &mut Vec<u8>) -> Result<()> {
@@ -16,36 +80,338 @@ pub fn write_value(value: &Value, buf: &mut Vec<u8>) -> Result<()> {
         Value::Object(o) => serde_json::to_writer(buf, &o).map_err(Into::into),
         Value::Array(o) => serde_json::to_writer(buf, &o).map_err(Into::into),
         Value::Null => Ok(()),
-        Value::Binary(_) => todo!(),
+        Value::Binary(b) => write!(buf, "{}", hex::encode(b)).map_err(Into::into),
     }
 }
 
-pub fn write<W: Write, R: ValueReader>(inner: W, reader: R, opts: Options) -> Result<()> {
-    let mut rows = reader.into_rows()?;
-    let mut writer = ::csv::WriterBuilder::new()
+fn quote_style(opts: &Options) -> ::csv::QuoteStyle {
+    match opts.quote_style {
+        QuoteStyle::Always => ::csv::QuoteStyle::Always,
+        QuoteStyle::Necessary => ::csv::QuoteStyle::Necessary,
+        QuoteStyle::NonNumeric => ::csv::QuoteStyle::NonNumeric,
+        QuoteStyle::Never => ::csv::QuoteStyle::Never,
+    }
+}
+
+fn builder(opts: &Options) -> ::csv::WriterBuilder {
+    let mut builder = ::csv::WriterBuilder::new();
+
+    builder
         .delimiter(opts.delimiter)
-        .from_writer(inner);
+        .quote(opts.quote)
+        .quote_style(quote_style(opts))
+        .terminator(match opts.line_terminator {
+            LineTerminator::Lf => ::csv::Terminator::Any(b'\n'),
+            LineTerminator::Crlf => ::csv::Terminator::CRLF,
+        });
 
-    let mut buf = vec![];
+    if let Some(escape) = opts.escape {
+        builder.double_quote(false).escape(escape);
+    }
+
+    builder
+}
+
+/// The CSV writer, or ours for the separators it can't write and to write
+/// nulls unquoted when the other fields are.
+enum RecordWriter<W: Write> {
+    Csv(Box<::csv::Writer<W>>),
+    Separated(SeparatorWriter<W>),
+}
+
+impl<W: Write> RecordWriter<W> {
+    fn new(inner: W, opts: &Options) -> Self {
+        let quoting = Quoting {
+            quote_style: quote_style(opts),
+            quote: opts.quote,
+            escape: opts.escape,
+        };
+        let terminator: &[u8] = match opts.line_terminator {
+            LineTerminator::Lf => b"\n",
+            LineTerminator::Crlf => b"\r\n",
+        };
 
-    for f in rows.fields() {
-        buf.clear();
-        write_value(
-            &Value::String(std::borrow::Cow::Borrowed(&f.name)),
-            &mut buf,
-        )?;
-        writer.write_field(&buf)?;
+        match (&opts.separator, &opts.quote_style) {
+            (Some(separator), _) => RecordWriter::Separated(SeparatorWriter::new(
+                inner,
+                separator.clone(),
+                quoting,
+                terminator,
+            )),
+            (None, QuoteStyle::Always | QuoteStyle::NonNumeric) => RecordWriter::Separated(
+                SeparatorWriter::with_delimiter(inner, opts.delimiter, quoting, terminator),
+            ),
+            (None, _) => RecordWriter::Csv(Box::new(builder(opts).from_writer(inner))),
+        }
     }
 
-    writer.write_record(None::<&[u8]>)?;
+    fn write_field(&mut self, field: &[u8]) -> Result<()> {
+        match self {
+            RecordWriter::Csv(writer) => writer.write_field(field)?,
+            RecordWriter::Separated(writer) => writer.write_field(field)?,
+        }
+        Ok(())
+    }
 
-    while let Some(row) = rows.next()? {
-        for value in row {
+    fn write_null(&mut self, null: &[u8]) -> Result<()> {
+        match self {
+            RecordWriter::Csv(writer) => writer.write_field(null)?,
+            RecordWriter::Separated(writer) => writer.write_null(null)?,
+        }
+        Ok(())
+    }
+
+    fn write_record_end(&mut self) -> Result<()> {
+        match self {
+            RecordWriter::Csv(writer) => writer.write_record(None::<&[u8]>)?,
+            RecordWriter::Separated(writer) => writer.write_record_end()?,
+        }
+        Ok(())
+    }
+
+    fn into_inner(self) -> Result<W> {
+        match self {
+            RecordWriter::Csv(writer) => writer.into_inner().map_err(|e| Error::output(e.error())),
+            RecordWriter::Separated(writer) => Ok(writer.into_inner()?),
+        }
+    }
+}
+
+fn write_csv<W: Write, R: ValueReader>(
+    mut inner: W,
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<W> {
+    if opts.bom {
+        inner.write_all("\u{feff}".as_bytes())?;
+    }
+
+    let mut writer = RecordWriter::new(inner, opts);
+
+    let mut buf = vec![];
+
+    if opts.has_headers {
+        for f in rows.fields() {
             buf.clear();
-            write_value(&value?, &mut buf)?;
+            write_value(
+                &Value::String(std::borrow::Cow::Borrowed(&f.name)),
+                &mut buf,
+            )?;
             writer.write_field(&buf)?;
         }
-        writer.write_record(None::<&[u8]>)?;
+
+        writer.write_record_end()?;
+    }
+
+    while let Some(row) = rows.next()? {
+        for value in row {
+            match value? {
+                Value::Null => writer.write_null(opts.null_string.as_bytes())?,
+                value => {
+                    buf.clear();
+                    write_value(&value, &mut buf)?;
+                    writer.write_field(&buf)?;
+                }
+            }
+        }
+        writer.write_record_end()?;
+    }
+
+    writer.into_inner()
+}
+
+fn write_rows<W: Write, R: ValueReader>(
+    inner: W,
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<()> {
+    if let Some(separator) = &opts.separator {
+        separator.validate()?;
+    }
+
+    let is_unicode = |e: &Encoding| {
+        [
+            encoding_rs::UTF_8,
+            encoding_rs::UTF_16LE,
+            encoding_rs::UTF_16BE,
+        ]
+        .contains(&e.0)
+    };
+    if opts.bom && matches!(&opts.encoding, Some(e) if !is_unicode(e)) {
+        return Err(Error::OutputError(
+            "A byte order mark can only be written in UTF-8 and UTF-16".into(),
+        ));
+    }
+
+    let writer = EncodingWriter::new(inner, opts.encoding.as_ref(), opts.unmappable);
+    write_csv(writer, rows, opts)?.finish()?;
+
+    Ok(())
+}
+
+pub fn write<W: Write, R: ValueReader>(inner: W, reader: R, opts: Options) -> Result<()> {
+    if opts.normalize.is_some() {
+        return Err(Error::OutputError(
+            "Normalized output is written to several files".into(),
+        ));
+    }
+
+    write_rows(inner, &mut reader.into_rows()?, &opts)
+}
+
+/// Writes the normalised tables of the records, calling `create` with the
+/// name of each table to get its output, the root table named `root_name`
+/// comes first.
+pub fn write_tables<W, R, F>(
+    mut reader: R,
+    root_name: &str,
+    opts: Options,
+    mut create: F,
+) -> Result<()>
+where
+    W: Write,
+    R: ValueReader,
+    F: FnMut(&str) -> Result<W>,
+{
+    let normalize_opts = opts.normalize.clone().unwrap_or_default();
+    let tables = normalize::tables(&normalize::infer_schema(&mut reader)?, root_name)?;
+
+    for (index, table) in tables.iter().enumerate() {
+        let table_reader =
+            normalize::TableReader::new(&mut reader, &tables, index, &normalize_opts);
+        write_rows(create(&table.name)?, &mut table_reader.into_rows()?, &opts)?;
     }
+
     Ok(())
 }
+
+#[test]
+fn csv_write_tables() {
+    let input = r#"{"id":"a","tags":[{"tag":"x"},{"tag":"y"}]}
+{"id":"b","tags":[{"tag":"z"}]}"#;
+
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let mut names = vec![];
+    let mut buffers = vec![vec![]; 2];
+    let mut outputs = buffers.iter_mut();
+
+    write_tables(
+        reader,
+        "posts",
+        Options {
+            normalize: Some(Default::default()),
+            ..Default::default()
+        },
+        |name| {
+            names.push(name.to_string());
+            Ok(outputs.next().unwrap())
+        },
+    )
+    .unwrap();
+
+    assert_eq!(names, vec!["posts", "posts_tags"]);
+    assert_eq!(
+        buffers
+            .into_iter()
+            .map(|data| String::from_utf8(data).unwrap())
+            .collect::<Vec<_>>(),
+        vec![
+            "_id,id\n1,a\n2,b\n",
+            "_id,posts_id,tag\n1,1,x\n2,1,y\n3,2,z\n"
+        ]
+    );
+}
+
+#[test]
+fn csv_write_options() {
+    let input = r#"{"id":1,"name":"Jérôme \"J\"","note":null}
+{"id":2,"name":"","note":"x"}"#;
+
+    let write_with = |opts: Options| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, opts).unwrap();
+        out
+    };
+
+    assert_eq!(
+        write_with(Options {
+            quote_style: QuoteStyle::NonNumeric,
+            escape: Some(b'\\'),
+            line_terminator: LineTerminator::Crlf,
+            has_headers: false,
+            null_string: "NULL".into(),
+            ..Default::default()
+        }),
+        "1,\"Jérôme \\\"J\\\"\",NULL\r\n2,\"\",\"x\"\r\n".as_bytes()
+    );
+
+    assert_eq!(
+        write_with(Options {
+            bom: true,
+            ..Default::default()
+        })[..3],
+        [0xef, 0xbb, 0xbf]
+    );
+    assert_eq!(
+        write_with(Options {
+            bom: true,
+            encoding: Some(Encoding(encoding_rs::UTF_16LE)),
+            ..Default::default()
+        })[..4],
+        [0xff, 0xfe, b'i', 0]
+    );
+    assert_eq!(
+        write_with(Options {
+            quote_style: QuoteStyle::Always,
+            has_headers: false,
+            ..Default::default()
+        }),
+        b"\"1\",\"J\xc3\xa9r\xc3\xb4me \"\"J\"\"\",\n\"2\",\"\",\"x\"\n"
+    );
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let opts = Options {
+        bom: true,
+        encoding: Some(Encoding(encoding_rs::WINDOWS_1252)),
+        ..Default::default()
+    };
+    assert!(write(vec![], reader, opts).is_err());
+
+    assert_eq!(
+        write_with(Options {
+            encoding: Some(Encoding(encoding_rs::WINDOWS_1252)),
+            ..Default::default()
+        }),
+        b"id,name,note\n1,\"J\xe9r\xf4me \"\"J\"\"\",\n2,,x\n"
+    );
+}
+
+#[test]
+fn csv_write_separator() {
+    let input = r#"{"a":"x||y","b":"","c":1}
+{"a":"x y","b":"z","c":2}"#;
+
+    let write = |separator: Separator| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        let opts = Options {
+            separator: Some(separator),
+            ..Default::default()
+        };
+        write(&mut out, reader, opts).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(
+        write(Separator::String("||".into())),
+        "a||b||c\n\"x||y\"||||1\nx y||z||2\n"
+    );
+    assert_eq!(
+        write(Separator::Whitespace),
+        "a b c\nx||y \"\" 1\n\"x y\" z 2\n"
+    );
+}
@@ -63,6 +63,16 @@ impl<'de> Deserialize<'de> for Value<'de> {
                 Ok(Value::String(Cow::Owned(value)))
             }
 
+            #[inline]
+            fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E> {
+                Ok(Value::Binary(value.to_vec()))
+            }
+
+            #[inline]
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Value::Binary(value))
+            }
+
             #[inline]
             fn visit_none<E>(self) -> Result<Self::Value, E> {
                 Ok(Value::Null)
@@ -1,4 +1,7 @@
-use crate::{avro, csv, excel, json, sql};
+use crate::{
+    avro, bson, cbor, csv, excel, fixed_width, html, ini, json, markdown, msgpack, sql, sqlite,
+    toml, xml, yaml,
+};
 
 #[derive(serde::Deserialize)]
 pub enum ReaderOptions {
@@ -10,6 +13,52 @@ pub enum ReaderOptions {
     Excel(excel::reader::Options),
     #[serde(rename = "avro")]
     Avro(avro::reader::Options),
+    #[serde(rename = "html")]
+    Html(html::reader::Options),
+    #[serde(rename = "markdown")]
+    Markdown(markdown::reader::Options),
+    #[serde(rename = "yaml")]
+    Yaml(yaml::reader::Options),
+    #[serde(rename = "ini")]
+    Ini(ini::reader::Options),
+    #[serde(rename = "msgpack")]
+    MsgPack(msgpack::reader::Options),
+    #[serde(rename = "cbor")]
+    Cbor(cbor::reader::Options),
+    #[serde(rename = "bson")]
+    Bson(bson::reader::Options),
+    #[serde(rename = "fixed_width")]
+    FixedWidth(fixed_width::reader::Options),
+    #[serde(rename = "sql")]
+    Sql(sql::reader::Options),
+    #[serde(rename = "sqlite_db")]
+    SqliteDb(sqlite::reader::Options),
+}
+
+/// Limits on the work done for a conversion.
+#[derive(Debug, Default, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// Most records read from the input
+    pub max_records: Option<usize>,
+    /// Largest input allowed in bytes
+    pub max_bytes: Option<u64>,
+    /// Longest CSV line allowed in bytes, instead of the reader's own, an
+    /// error for the other formats
+    pub max_line_length: Option<usize>,
+    /// Longest JSON record or NDJSON line allowed in bytes, instead of the
+    /// reader's own, an error for the other formats
+    pub max_record_length: Option<usize>,
+}
+
+/// The reader options along with the limits of the conversion, as in
+/// `{"csv": {...}, "limits": {...}}`.
+#[derive(serde::Deserialize)]
+pub struct InputOptions {
+    #[serde(flatten)]
+    pub reader: ReaderOptions,
+    #[serde(default)]
+    pub limits: Limits,
 }
 
 #[derive(serde::Deserialize)]
@@ -21,9 +70,27 @@ pub enum WriterOptions {
     #[serde(rename = "sql")]
     Sql(sql::writer::Options),
     #[serde(rename = "html")]
-    Html {},
+    Html(html::writer::Options),
     #[serde(rename = "xml")]
-    Xml {},
+    Xml(xml::writer::Options),
     #[serde(rename = "avro")]
     Avro(avro::writer::Options),
+    #[serde(rename = "markdown")]
+    Markdown(markdown::writer::Options),
+    #[serde(rename = "yaml")]
+    Yaml(yaml::writer::Options),
+    #[serde(rename = "toml")]
+    Toml(toml::writer::Options),
+    #[serde(rename = "ini")]
+    Ini(ini::writer::Options),
+    #[serde(rename = "msgpack")]
+    MsgPack(msgpack::writer::Options),
+    #[serde(rename = "cbor")]
+    Cbor(cbor::writer::Options),
+    #[serde(rename = "bson")]
+    Bson(bson::writer::Options),
+    #[serde(rename = "fixed_width")]
+    FixedWidth(fixed_width::writer::Options),
+    #[serde(rename = "sqlite_db")]
+    SqliteDb(sqlite::writer::Options),
 }
@@ -20,6 +20,38 @@ pub enum Error {
     InputError(String),
     #[error("Output Error: {0}")]
     OutputError(String),
+    #[error("{0}")]
+    LimitExceeded(LimitExceeded),
+}
+
+/// What a [`LimitExceeded`] error is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Limit {
+    #[serde(rename = "line_length")]
+    LineLength,
+    #[serde(rename = "record_length")]
+    RecordLength,
+    #[serde(rename = "records")]
+    Records,
+    #[serde(rename = "bytes")]
+    Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub max: u64,
+}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            Limit::LineLength => write!(f, "Line length exceeds {} bytes", self.max),
+            Limit::RecordLength => write!(f, "Record length exceeds {} bytes", self.max),
+            Limit::Records => write!(f, "Number of records exceeds {}", self.max),
+            Limit::Bytes => write!(f, "Input size exceeds {} bytes", self.max),
+        }
+    }
 }
 
 impl Error {
@@ -30,6 +62,18 @@ impl Error {
     pub fn output<E: Display>(e: E) -> Error {
         Error::OutputError(e.to_string())
     }
+
+    pub fn limit(limit: Limit, max: u64) -> Error {
+        Error::LimitExceeded(LimitExceeded { limit, max })
+    }
+}
+
+/// Takes back our errors passed through readers as I/O errors.
+fn unwrap_io_error(e: std::io::Error) -> std::result::Result<Error, std::io::Error> {
+    match e.get_ref() {
+        Some(inner) if inner.is::<Error>() => Ok(*e.into_inner().unwrap().downcast().unwrap()),
+        _ => Err(e),
+    }
 }
 
 impl Serialize for Error {
@@ -73,7 +117,7 @@ impl From<serde_json::Error> for Error {
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Error {
-        Error::IoError(e)
+        unwrap_io_error(e).unwrap_or_else(Error::IoError)
     }
 }
 
@@ -85,6 +129,15 @@ impl From<arrow2::error::Error> for Error {
 
 impl From<::csv::Error> for Error {
     fn from(e: ::csv::Error) -> Error {
-        Error::CsvError(e)
+        if !e.is_io_error() {
+            return Error::CsvError(e);
+        }
+
+        match e.into_kind() {
+            ::csv::ErrorKind::Io(e) => {
+                unwrap_io_error(e).unwrap_or_else(|e| Error::CsvError(e.into()))
+            }
+            _ => unreachable!(),
+        }
     }
 }
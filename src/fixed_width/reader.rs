@@ -0,0 +1,288 @@
+use crate::{
+    csv::reader::detect_encoding,
+    error::{Error, Result},
+    io::Encoding,
+    reader::{self, Object, Value},
+    DataType,
+};
+
+use std::borrow::Cow;
+use std::io::Cursor;
+
+const INFER_SAMPLE_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum Align {
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+}
+
+/// A column of the layout, shared by the reader and the writer.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Column {
+    pub name: String,
+    /// Position of the first character of the column, starting at 0
+    pub start: usize,
+    pub width: usize,
+    /// Type of the column, detected from each value when missing
+    #[serde(default)]
+    pub data_type: Option<DataType>,
+    #[serde(default = "default_trim")]
+    pub trim: bool,
+    /// Alignment when writing, defaults to right for numbers and left for
+    /// everything else
+    #[serde(default)]
+    pub align: Option<Align>,
+}
+
+fn default_trim() -> bool {
+    true
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Column layout, inferred from the whitespace alignment of the first
+    /// lines when missing
+    pub columns: Option<Vec<Column>>,
+    pub has_headers: bool,
+    pub encoding: Option<Encoding>,
+}
+
+pub struct Reader {
+    text: String,
+    start_pos: usize,
+    offset: usize,
+    line_number: usize,
+    columns: Vec<Column>,
+}
+
+impl Reader {
+    pub fn new<T: AsRef<[u8]>>(data: T, options: Options) -> Result<Self> {
+        let data = data.as_ref();
+        let mut cursor = Cursor::new(data);
+        let encoding = detect_encoding(&mut cursor, options.encoding)?;
+        let (text, _) = encoding
+            .0
+            .decode_without_bom_handling(&data[cursor.position() as usize..]);
+        let text = text.into_owned();
+
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+
+        let columns = match options.columns {
+            Some(columns) => columns,
+            None => infer_columns(lines.clone().take(INFER_SAMPLE_LINES), options.has_headers),
+        };
+
+        let start_pos = if options.has_headers {
+            match lines.next() {
+                Some(header) => header.as_ptr() as usize - text.as_ptr() as usize + header.len(),
+                None => text.len(),
+            }
+        } else {
+            0
+        };
+
+        Ok(Self {
+            text,
+            start_pos,
+            offset: start_pos,
+            line_number: 0,
+            columns,
+        })
+    }
+
+    pub fn columns(&self) -> &Vec<Column> {
+        &self.columns
+    }
+}
+
+/// Slices `width` characters starting at character `start`.
+fn slice_chars(line: &str, start: usize, width: usize) -> &str {
+    if line.is_ascii() {
+        let from = start.min(line.len());
+        let to = start.saturating_add(width).min(line.len());
+        return &line[from..to];
+    }
+
+    let byte_at = |n: usize| {
+        line.char_indices()
+            .nth(n)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+    };
+
+    &line[byte_at(start)..byte_at(start.saturating_add(width))]
+}
+
+fn parse_value<'a>(column: &Column, raw: &'a str, line_number: usize) -> Result<Value<'a>> {
+    let raw = if column.trim { raw.trim() } else { raw };
+
+    let invalid = || {
+        Error::InputError(format!(
+            "Invalid value `{}` for column `{}` on line {}",
+            raw, column.name, line_number
+        ))
+    };
+
+    Ok(match &column.data_type {
+        Some(DataType::String) => Value::String(Cow::Borrowed(raw)),
+        _ if raw.is_empty() => Value::Null,
+        Some(DataType::Int) => Value::Int(raw.parse().map_err(|_| invalid())?),
+        Some(DataType::Float) => Value::Float(raw.parse().map_err(|_| invalid())?),
+        Some(DataType::Bool) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "t" | "y" | "yes" | "1" => Value::Bool(true),
+            "false" | "f" | "n" | "no" | "0" => Value::Bool(false),
+            _ => return Err(invalid()),
+        },
+        _ => reader::parse_bytes(raw.as_bytes()),
+    })
+}
+
+impl reader::ValueReader for Reader {
+    fn reset(&mut self) {
+        self.offset = self.start_pos;
+        self.line_number = 0;
+    }
+
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let (start, end) = loop {
+            if self.offset >= self.text.len() {
+                return Ok(None);
+            }
+
+            let rest = &self.text[self.offset..];
+            let (len, next) = match rest.find('\n') {
+                Some(i) => (i, i + 1),
+                None => (rest.len(), rest.len()),
+            };
+
+            let start = self.offset;
+            self.offset += next;
+            self.line_number += 1;
+
+            if !rest[..len].trim().is_empty() {
+                break (start, start + len);
+            }
+        };
+
+        let line = &self.text[start..end];
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let mut row = Object::with_capacity(self.columns.len());
+
+        for column in &self.columns {
+            let raw = slice_chars(line, column.start, column.width);
+            row.insert(
+                Cow::Borrowed(column.name.as_str()),
+                parse_value(column, raw, self.line_number)?,
+            );
+        }
+
+        Ok(Some(Value::Object(row)))
+    }
+}
+
+/// Guesses column boundaries from the character positions that are blank on
+/// every sampled line, each column extends up to the start of the next one
+/// and the last one up to the end of the longest line.
+pub fn infer_columns<'a, I: Iterator<Item = &'a str>>(lines: I, has_headers: bool) -> Vec<Column> {
+    let lines = lines
+        .map(|l| l.trim_end().chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let mut is_blank = vec![true; width];
+
+    for line in &lines {
+        for (i, c) in line.iter().enumerate() {
+            if !c.is_whitespace() {
+                is_blank[i] = false;
+            }
+        }
+    }
+
+    let starts = (0..width)
+        .filter(|&i| !is_blank[i] && (i == 0 || is_blank[i - 1]))
+        .collect::<Vec<_>>();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(width);
+
+            let name = match lines.first() {
+                Some(header) if has_headers => header
+                    .iter()
+                    .skip(start)
+                    .take(end - start)
+                    .collect::<String>()
+                    .trim()
+                    .to_string(),
+                _ => String::new(),
+            };
+
+            Column {
+                name: if name.is_empty() {
+                    format!("column_{}", i + 1)
+                } else {
+                    name
+                },
+                start,
+                width: end - start,
+                data_type: None,
+                trim: true,
+                align: None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn fixed_width_infer() {
+    use crate::reader::ValueReader;
+
+    let input = "ID   NAME        AMOUNT\n\
+                 1    Jêroño        12.50\n\
+                 22   Bob Smith         7\n\
+                 \n\
+                 333  Carol\n";
+
+    let mut reader = Reader::new(
+        input,
+        Options {
+            has_headers: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        reader
+            .columns()
+            .iter()
+            .map(|c| (c.name.as_str(), c.start, c.width))
+            .collect::<Vec<_>>(),
+        vec![("ID", 0, 5), ("NAME", 5, 12), ("AMOUNT", 17, 7)]
+    );
+
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"ID": 1, "NAME": "Jêroño", "AMOUNT": "12.50"})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"ID": 22, "NAME": "Bob Smith", "AMOUNT": 7})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"ID": 333, "NAME": "Carol", "AMOUNT": null})
+    );
+    assert!(reader.next().unwrap().is_none());
+
+    reader.reset();
+    assert!(reader.next().unwrap().is_some());
+}
@@ -0,0 +1,272 @@
+use crate::fixed_width::reader::{Align, Column};
+use crate::reader::ValueReader;
+use crate::{DataType, Error, Result, Value};
+use std::io::Write;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Output layout, the same as the reader's, every field is written with
+    /// the width of its longest value when empty
+    pub columns: Vec<Column>,
+    pub has_headers: bool,
+    /// Written between columns when `columns` is empty, the gaps of the
+    /// given layout are filled with spaces
+    pub separator: String,
+    /// Cuts the values wider than their column instead of failing
+    pub truncate: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            columns: vec![],
+            has_headers: true,
+            separator: " ".into(),
+            truncate: false,
+        }
+    }
+}
+
+pub fn write_value(buf: &mut String, value: &Value) -> Result<()> {
+    match value {
+        Value::Int(i) => buf.push_str(&i.to_string()),
+        Value::String(s) => buf.extend(s.chars().map(|c| if c.is_control() { ' ' } else { c })),
+        Value::Float(s) => buf.push_str(&s.to_string()),
+        Value::Bool(s) => buf.push_str(&s.to_string()),
+        Value::Object(o) => buf.push_str(&serde_json::to_string(o)?),
+        Value::Array(o) => buf.push_str(&serde_json::to_string(o)?),
+        Value::Null => {}
+        Value::Binary(b) => buf.push_str(&hex::encode(b)),
+    };
+
+    Ok(())
+}
+
+/// Pads `cell` to exactly the width of `column`, truncating longer values
+/// when `truncate` is set.
+fn write_cell<W: Write>(
+    writer: &mut W,
+    cell: &str,
+    column: &Column,
+    align: Align,
+    truncate: bool,
+) -> Result<()> {
+    let width = column.width;
+    let len = cell.chars().count();
+
+    if len > width && !truncate {
+        return Err(Error::OutputError(format!(
+            "`{}` doesn't fit in the {} characters of column `{}`, set `truncate` to cut it",
+            cell, width, column.name
+        )));
+    }
+
+    if len > width {
+        let end = cell
+            .char_indices()
+            .nth(width)
+            .map(|(i, _)| i)
+            .unwrap_or(cell.len());
+        writer.write_all(&cell.as_bytes()[..end])?;
+        return Ok(());
+    }
+
+    let padding = " ".repeat(width - len);
+
+    match align {
+        Align::Left => write!(writer, "{}{}", cell, padding)?,
+        Align::Right => write!(writer, "{}{}", padding, cell)?,
+    }
+
+    Ok(())
+}
+
+fn default_align(data_type: Option<&DataType>) -> Align {
+    match data_type {
+        Some(DataType::Int | DataType::Float) => Align::Right,
+        _ => Align::Left,
+    }
+}
+
+pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R, opts: Options) -> Result<()> {
+    let mut rows = reader.into_rows()?;
+    let mut buf = String::new();
+
+    let columns = if opts.columns.is_empty() {
+        let mut widths = rows
+            .fields()
+            .iter()
+            .map(|f| {
+                if opts.has_headers {
+                    f.name.chars().count()
+                } else {
+                    0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        while let Some(row) = rows.next()? {
+            for (width, value) in widths.iter_mut().zip(row) {
+                buf.clear();
+                write_value(&mut buf, &value?)?;
+                *width = (*width).max(buf.chars().count());
+            }
+        }
+
+        rows.reset();
+
+        let separator_width = opts.separator.chars().count();
+        let mut start = 0;
+
+        rows.fields()
+            .iter()
+            .zip(widths)
+            .map(|(f, width)| {
+                let column = Column {
+                    name: f.name.clone(),
+                    start,
+                    width,
+                    data_type: None,
+                    trim: true,
+                    align: None,
+                };
+                start += width + separator_width;
+                column
+            })
+            .collect()
+    } else {
+        opts.columns.clone()
+    };
+
+    // Spaces before each column, or the separator when the layout is inferred
+    let mut gaps = vec![];
+    let mut end = 0;
+    for column in &columns {
+        if column.start < end {
+            return Err(Error::OutputError(format!(
+                "Column `{}` starts before the end of the previous one",
+                column.name
+            )));
+        }
+        gaps.push(if opts.columns.is_empty() && end > 0 {
+            opts.separator.clone()
+        } else {
+            " ".repeat(column.start - end)
+        });
+        end = column.start + column.width;
+    }
+
+    // Position of each output column in the rows
+    let indexes = columns
+        .iter()
+        .map(|c| {
+            rows.fields()
+                .iter()
+                .position(|f| f.name == c.name)
+                .ok_or_else(|| Error::OutputError(format!("Column `{}` not found", c.name)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let aligns = columns
+        .iter()
+        .zip(&indexes)
+        .map(|(c, &index)| {
+            c.align.unwrap_or_else(|| {
+                default_align(
+                    c.data_type
+                        .as_ref()
+                        .or(Some(&rows.fields()[index].data_type)),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if opts.has_headers {
+        for ((column, gap), align) in columns.iter().zip(&gaps).zip(&aligns) {
+            writer.write_all(gap.as_bytes())?;
+            write_cell(&mut writer, &column.name, column, *align, opts.truncate)?;
+        }
+        writer.write_all(b"\n")?;
+    }
+
+    let mut cells = vec![String::new(); rows.fields().len()];
+
+    while let Some(row) = rows.next()? {
+        for (cell, value) in cells.iter_mut().zip(row) {
+            cell.clear();
+            write_value(cell, &value?)?;
+        }
+
+        for (((column, gap), &index), align) in columns.iter().zip(&gaps).zip(&indexes).zip(&aligns)
+        {
+            writer.write_all(gap.as_bytes())?;
+            write_cell(&mut writer, &cells[index], column, *align, opts.truncate)?;
+        }
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fixed_width_write() {
+    use crate::fixed_width::reader::{Options as ReaderOptions, Reader};
+
+    let input = r#"{"id":1,"name":"Jêroño","amount":12.5}
+{"id":22,"name":"Bob Smith Junior","amount":null}"#;
+    let write_with = |opts: Options| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, opts).map(|_| String::from_utf8(out).unwrap())
+    };
+
+    assert_eq!(
+        write_with(Default::default()).unwrap(),
+        "id name             amount\n 1 Jêroño             12.5\n22 Bob Smith Junior       \n"
+    );
+
+    let columns = serde_json::from_str::<Vec<Column>>(
+        r#"[{"name":"id","start":0,"width":4},
+            {"name":"name","start":6,"width":8,"align":"right"}]"#,
+    )
+    .unwrap();
+    let error = write_with(Options {
+        columns: columns.clone(),
+        has_headers: false,
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(error.to_string().contains("`Bob Smith Junior`"));
+
+    let output = write_with(Options {
+        columns: columns.clone(),
+        has_headers: false,
+        truncate: true,
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(output, "   1    Jêroño\n  22  Bob Smit\n");
+
+    let mut reader = Reader::new(
+        output,
+        ReaderOptions {
+            columns: Some(columns.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 1, "name": "Jêroño"})
+    );
+
+    let mut missing = columns;
+    missing[1].name = "nmae".into();
+    assert!(write_with(Options {
+        columns: missing,
+        ..Default::default()
+    })
+    .is_err());
+}
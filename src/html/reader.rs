@@ -0,0 +1,369 @@
+use crate::{
+    csv::reader::detect_encoding,
+    error::Error,
+    error::Result,
+    io::Encoding,
+    reader::{self, Object, Value},
+};
+
+use scraper::{ElementRef, Html, Node, Selector};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::Cursor;
+
+pub struct Reader {
+    index: usize,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Tables matching it or inside the elements matching it
+    pub selector: Option<String>,
+    pub index: usize,
+    /// Takes the first row as the header when it is in `<thead>` or only has
+    /// `<th>` cells
+    pub has_headers: bool,
+    /// Input encoding, from the BOM, the `<meta>` charset or a guess when not set
+    pub encoding: Option<Encoding>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            selector: None,
+            index: 0,
+            has_headers: true,
+            encoding: None,
+        }
+    }
+}
+
+impl Reader {
+    pub fn new<T: AsRef<[u8]>>(data: T, options: Options) -> Result<Self> {
+        let data = data.as_ref();
+
+        // The BOM comes first, then the charset declared in the document
+        let declared = match encoding_rs::Encoding::for_bom(data) {
+            Some(_) => None,
+            None => declared_charset(data),
+        };
+        let mut cursor = Cursor::new(data);
+        let encoding = detect_encoding(&mut cursor, options.encoding.or(declared))?;
+        let (text, _) = encoding
+            .0
+            .decode_without_bom_handling(&data[cursor.position() as usize..]);
+
+        let document = Html::parse_document(&text);
+
+        let selector_str = options.selector.as_deref().unwrap_or("table");
+        let selector = Selector::parse(selector_str).map_err(|e| {
+            Error::InputError(format!("Invalid selector `{}`: {:?}", selector_str, e))
+        })?;
+        let table_selector = Selector::parse("table").unwrap();
+
+        let mut seen = HashSet::new();
+        let table = document
+            .select(&selector)
+            .flat_map(|el| match el.value().name() {
+                "table" => vec![el],
+                _ => el.select(&table_selector).collect(),
+            })
+            .filter(|el| seen.insert(el.id()))
+            .nth(options.index)
+            .ok_or_else(|| {
+                Error::InputError(format!(
+                    "Table number {} matching `{}` not found",
+                    options.index, selector_str
+                ))
+            })?;
+
+        let has_headers = options.has_headers && table_rows(table).first().is_some_and(is_header);
+        let mut rows = read_grid(table);
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        // Cells past the header get generated names rather than being dropped
+        let mut header = match has_headers {
+            true => rows.remove(0),
+            false => vec![],
+        };
+        header.resize(width, String::new());
+        let headers = unique_headers(header);
+
+        Ok(Self {
+            index: 0,
+            headers,
+            rows,
+        })
+    }
+}
+
+impl reader::ValueReader for Reader {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let Some(cells) = self.rows.get(self.index) else {
+            return Ok(None);
+        };
+
+        let row = self
+            .headers
+            .iter()
+            .map(|h| Cow::Borrowed(h.as_str()))
+            .zip(cells.iter().map(|s| reader::parse_bytes(s.as_bytes())))
+            .collect::<Object>();
+
+        self.index += 1;
+
+        Ok(Some(Value::Object(row)))
+    }
+}
+
+fn child_elements<'a>(el: ElementRef<'a>) -> impl Iterator<Item = ElementRef<'a>> {
+    el.children().filter_map(ElementRef::wrap)
+}
+
+fn table_rows(table: ElementRef<'_>) -> Vec<ElementRef<'_>> {
+    let mut rows = vec![];
+
+    for child in child_elements(table) {
+        match child.value().name() {
+            "tr" => rows.push(child),
+            "thead" | "tbody" | "tfoot" => {
+                rows.extend(child_elements(child).filter(|el| el.value().name() == "tr"))
+            }
+            _ => {}
+        }
+    }
+
+    rows
+}
+
+/// Whether a row is a header row, the one in `<thead>` or one of `<th>` cells.
+fn is_header(tr: &ElementRef<'_>) -> bool {
+    let in_thead = tr
+        .parent()
+        .and_then(ElementRef::wrap)
+        .is_some_and(|parent| parent.value().name() == "thead");
+    let cells = child_elements(*tr)
+        .filter(|el| matches!(el.value().name(), "td" | "th"))
+        .collect::<Vec<_>>();
+
+    in_thead || !cells.is_empty() && cells.iter().all(|el| el.value().name() == "th")
+}
+
+/// Encoding named by a `<meta>` charset in the start of the document.
+fn declared_charset(data: &[u8]) -> Option<Encoding> {
+    let start = data[..data.len().min(1024)].to_ascii_lowercase();
+    let position = start.windows(8).position(|w| w == b"charset=")? + 8;
+
+    let label = start[position..]
+        .iter()
+        .skip_while(|b| matches!(b, b'"' | b'\''))
+        .take_while(|b| !matches!(b, b'"' | b'\'' | b';' | b'>' | b'/') && !b.is_ascii_whitespace())
+        .copied()
+        .collect::<Vec<_>>();
+    let encoding = encoding_rs::Encoding::for_label(&label)?;
+
+    // A declaration readable as ASCII can't be in UTF-16
+    if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+        return Some(Encoding(encoding_rs::UTF_8));
+    }
+
+    Some(Encoding(encoding))
+}
+
+fn span_attr(el: ElementRef<'_>, name: &str) -> usize {
+    el.value()
+        .attr(name)
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(1)
+        .clamp(1, 1000)
+}
+
+/// Lays out the rows of a table into a rectangular grid, copying the text of
+/// cells spanning multiple columns or rows into every slot they cover.
+fn read_grid(table: ElementRef<'_>) -> Vec<Vec<String>> {
+    let mut grid: Vec<Vec<String>> = vec![];
+    // Cells from previous rows still spanning down, indexed by column
+    let mut pending: Vec<Option<(usize, String)>> = vec![];
+
+    for tr in table_rows(table) {
+        let mut row = vec![];
+        let mut cells = child_elements(tr).filter(|el| matches!(el.value().name(), "td" | "th"));
+
+        loop {
+            let col = row.len();
+
+            if let Some(Some((remaining, text))) = pending.get_mut(col) {
+                row.push(text.clone());
+                *remaining -= 1;
+                if *remaining == 0 {
+                    pending[col] = None;
+                }
+                continue;
+            }
+
+            let Some(cell) = cells.next() else {
+                break;
+            };
+
+            let text = cell_text(cell);
+            let colspan = span_attr(cell, "colspan");
+            let rowspan = span_attr(cell, "rowspan");
+
+            for _ in 0..colspan {
+                if rowspan > 1 {
+                    let col = row.len();
+                    if pending.len() <= col {
+                        pending.resize(col + 1, None);
+                    }
+                    pending[col] = Some((rowspan - 1, text.clone()));
+                }
+                row.push(text.clone());
+            }
+        }
+
+        // Row spans reaching past the last cell of this row
+        for (col, span) in pending.iter_mut().enumerate().skip(row.len()) {
+            if let Some((remaining, text)) = span {
+                row.resize(col, String::new());
+                row.push(text.clone());
+                *remaining -= 1;
+                if *remaining == 0 {
+                    *span = None;
+                }
+            }
+        }
+
+        grid.push(row);
+    }
+
+    grid
+}
+
+/// Text content of a cell with inline markup stripped and whitespace collapsed.
+fn cell_text(cell: ElementRef<'_>) -> String {
+    let mut text = String::new();
+
+    for node in cell.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(el) if matches!(el.name(), "br" | "p" | "div" | "li") => text.push(' '),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn unique_headers(row: Vec<String>) -> Vec<String> {
+    let mut headers: Vec<String> = Vec::with_capacity(row.len());
+
+    for (i, name) in row.into_iter().enumerate() {
+        let name = if name.is_empty() {
+            format!("column_{}", i + 1)
+        } else {
+            name
+        };
+
+        let mut unique = name.clone();
+        let mut n = 1;
+
+        while headers.contains(&unique) {
+            n += 1;
+            unique = format!("{}_{}", name, n);
+        }
+
+        headers.push(unique);
+    }
+
+    headers
+}
+
+#[test]
+fn html_table_spans() {
+    use crate::reader::ValueReader;
+
+    let html = r#"
+<html><body>
+<table id="other"><tr><td>ignored</td></tr></table>
+<table class="report">
+  <thead><tr><th>Name</th><th colspan="2">Score</th></tr></thead>
+  <tbody>
+    <tr><td rowspan="2"><b>Alice</b></td><td>1</td><td>2</td></tr>
+    <tr><td>3</td><td><a href="/x">four</a></td></tr>
+  </tbody>
+</table>
+</body></html>"#;
+
+    let mut reader = Reader::new(
+        html,
+        Options {
+            selector: Some(".report".into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(reader.headers, vec!["Name", "Score", "Score_2"]);
+
+    let row = reader.next().unwrap().unwrap();
+    assert_eq!(
+        row,
+        crate::value!({"Name": "Alice", "Score": 1, "Score_2": 2})
+    );
+
+    let row = reader.next().unwrap().unwrap();
+    assert_eq!(
+        row,
+        crate::value!({"Name": "Alice", "Score": 3, "Score_2": "four"})
+    );
+
+    assert!(reader.next().unwrap().is_none());
+}
+
+#[test]
+fn html_table_headers() {
+    use crate::reader::ValueReader;
+
+    let read = |html: &[u8], options: Options| {
+        let mut reader = Reader::new(html, options).unwrap();
+        let mut rows = vec![];
+        while let Some(row) = reader.next().unwrap() {
+            rows.push(serde_json::to_string(&row).unwrap());
+        }
+        rows
+    };
+
+    // Tables inside the matched element, the first row isn't a header
+    let html = br#"<div class="wrap"><table>
+<tr><td>a</td><td>1</td></tr>
+<tr><td>b</td><td>2</td><td>x</td></tr>
+</table></div>"#;
+    let options = Options {
+        selector: Some(".wrap".into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        read(html, options),
+        vec![
+            r#"{"column_1":"a","column_2":1}"#,
+            r#"{"column_1":"b","column_2":2,"column_3":"x"}"#
+        ]
+    );
+
+    // Extra cells after a header of `<th>`, in the declared charset
+    let html = b"<html><head><meta charset=\"windows-1252\"></head><body><table>
+<tr><th>name</th><th>column_3</th></tr>
+<tr><td>caf\xe9</td><td>1</td><td>2</td></tr>
+</table></body></html>";
+    assert_eq!(
+        read(html, Default::default()),
+        vec![r#"{"name":"café","column_3":1,"column_3_2":2}"#]
+    );
+}
@@ -1,45 +1,187 @@
+use crate::io::{Encoding, EncodingWriter, Unmappable};
 use crate::reader::ValueReader;
-use crate::{Result, Value};
+use crate::{DataType, Result, Value};
 use std::io::Write;
 
-pub fn write_value<W: Write>(writer: &mut W, buf: &mut String, value: &Value) -> Result<()> {
-    match value {
-        Value::Int(i) => write!(writer, "{}", i),
-        Value::String(s) => html_escape::encode_text_to_writer(s, writer),
-        Value::Float(s) => write!(writer, "{}", s),
-        Value::Bool(true) => writer.write_all(b"true"),
-        Value::Bool(false) => writer.write_all(b"false"),
-        Value::Object(o) => {
+const STYLESHEET: &str = "    <style>
+      body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", Helvetica, Arial, sans-serif; font-size: 14px; color: #24292f; }
+      table { border-collapse: collapse; }
+      caption { font-weight: bold; padding: 6px; text-align: left; }
+      th, td { border: 1px solid #d0d7de; padding: 4px 8px; vertical-align: top; }
+      th { background-color: #f6f8fa; font-weight: 600; text-align: left; }
+      tbody tr:nth-child(even) { background-color: #f6f8fa; }
+      td table, td dl, td ul { margin: 0; }
+      dt { font-weight: 600; }
+      dd { margin-left: 1em; }
+    </style>
+";
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Nested {
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    #[serde(rename = "table")]
+    Table,
+    #[serde(rename = "list")]
+    List,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    pub header_cells: bool,
+    pub document: bool,
+    pub title: Option<String>,
+    pub table_id: Option<String>,
+    pub table_class: Option<String>,
+    pub caption: Option<String>,
+    pub null_value: String,
+    pub nested: Nested,
+    pub align_numbers: bool,
+    /// Output encoding, UTF-8 when not set
+    pub encoding: Option<Encoding>,
+    pub unmappable: Unmappable,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            header_cells: false,
+            document: false,
+            title: None,
+            table_id: None,
+            table_class: None,
+            caption: None,
+            null_value: "NULL".into(),
+            nested: Nested::Json,
+            align_numbers: false,
+            encoding: None,
+            unmappable: Unmappable::default(),
+        }
+    }
+}
+
+pub fn write_value<W: Write>(
+    writer: &mut W,
+    buf: &mut String,
+    value: &Value,
+    opts: &Options,
+) -> Result<()> {
+    match (value, &opts.nested) {
+        (Value::Int(i), _) => write!(writer, "{}", i),
+        (Value::String(s), _) => html_escape::encode_text_to_writer(s, writer),
+        (Value::Float(s), _) => write!(writer, "{}", s),
+        (Value::Bool(true), _) => writer.write_all(b"true"),
+        (Value::Bool(false), _) => writer.write_all(b"false"),
+        (Value::Object(o), Nested::Json) => {
             unsafe { serde_json::to_writer(buf.as_mut_vec(), &o)? };
             html_escape::encode_text_to_writer(buf, writer)
         }
-        Value::Array(o) => {
+        (Value::Array(o), Nested::Json) => {
             unsafe { serde_json::to_writer(buf.as_mut_vec(), &o)? };
             html_escape::encode_text_to_writer(buf, writer)
         }
-        Value::Null => write!(writer, "NULL"),
-        Value::Binary(_) => todo!(),
+        (Value::Object(o), Nested::Table) => {
+            writer.write_all(b"<table>")?;
+            for (key, value) in o {
+                writer.write_all(b"<tr><th>")?;
+                html_escape::encode_text_to_writer(key, writer)?;
+                writer.write_all(b"</th><td>")?;
+                write_value(writer, buf, value, opts)?;
+                writer.write_all(b"</td></tr>")?;
+            }
+            writer.write_all(b"</table>")
+        }
+        (Value::Object(o), Nested::List) => {
+            writer.write_all(b"<dl>")?;
+            for (key, value) in o {
+                writer.write_all(b"<dt>")?;
+                html_escape::encode_text_to_writer(key, writer)?;
+                writer.write_all(b"</dt><dd>")?;
+                write_value(writer, buf, value, opts)?;
+                writer.write_all(b"</dd>")?;
+            }
+            writer.write_all(b"</dl>")
+        }
+        (Value::Array(o), _) => {
+            writer.write_all(b"<ul>")?;
+            for value in o {
+                writer.write_all(b"<li>")?;
+                write_value(writer, buf, value, opts)?;
+                writer.write_all(b"</li>")?;
+            }
+            writer.write_all(b"</ul>")
+        }
+        (Value::Null, _) => html_escape::encode_text_to_writer(&opts.null_value, writer),
+        (Value::Binary(b), _) => write!(writer, "{}", hex::encode(b)),
     }
     .map_err(Into::into)
 }
 
-pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R) -> Result<()> {
+fn write_attribute<W: Write>(writer: &mut W, name: &str, value: &Option<String>) -> Result<()> {
+    if let Some(value) = value {
+        write!(writer, " {}=\"", name)?;
+        html_escape::encode_double_quoted_attribute_to_writer(value, writer)?;
+        writer.write_all(b"\"")?;
+    }
+
+    Ok(())
+}
+
+fn cell_attributes(data_type: &DataType, opts: &Options) -> &'static str {
+    match data_type {
+        DataType::Int | DataType::Float if opts.align_numbers => " style=\"text-align: right\"",
+        _ => "",
+    }
+}
+
+pub fn write<W: Write, R: ValueReader>(writer: W, reader: R, opts: Options) -> Result<()> {
+    let mut writer = EncodingWriter::new(writer, opts.encoding.as_ref(), opts.unmappable);
     let mut buf = String::new();
+    let mut rows = reader.into_rows()?;
+
+    let header_tag = if opts.header_cells { "th" } else { "td" };
+    let attributes = rows
+        .fields()
+        .iter()
+        .map(|f| cell_attributes(&f.data_type, &opts))
+        .collect::<Vec<_>>();
+
+    if opts.document {
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html>")?;
+        writeln!(writer, "  <head>")?;
+        let charset = opts.encoding.map_or("utf-8", |e| e.0.name());
+        writeln!(writer, "    <meta charset=\"{}\">", charset)?;
+        if let Some(title) = &opts.title {
+            write!(writer, "    <title>")?;
+            html_escape::encode_text_to_writer(title, &mut writer)?;
+            writeln!(writer, "</title>")?;
+        }
+        writer.write_all(STYLESHEET.as_bytes())?;
+        writeln!(writer, "  </head>")?;
+        writeln!(writer, "  <body>")?;
+    }
+
+    write!(writer, "<table")?;
+    write_attribute(&mut writer, "id", &opts.table_id)?;
+    write_attribute(&mut writer, "class", &opts.table_class)?;
+    writeln!(writer, ">")?;
+
+    if let Some(caption) = &opts.caption {
+        write!(writer, "  <caption>")?;
+        html_escape::encode_text_to_writer(caption, &mut writer)?;
+        writeln!(writer, "</caption>")?;
+    }
 
-    writeln!(writer, "<table>")?;
     writeln!(writer, "  <thead>")?;
     writeln!(writer, "    <tr>")?;
 
-    let mut rows = reader.into_rows()?;
-
-    for field in rows.fields() {
-        write!(writer, "      <td>")?;
-        write_value(
-            &mut writer,
-            &mut buf,
-            &Value::String(std::borrow::Cow::Borrowed(&field.name)),
-        )?;
-        writeln!(writer, "</td>")?;
+    for (field, attributes) in rows.fields().iter().zip(&attributes) {
+        write!(writer, "      <{}{}>", header_tag, attributes)?;
+        html_escape::encode_text_to_writer(&field.name, &mut writer)?;
+        writeln!(writer, "</{}>", header_tag)?;
     }
 
     writeln!(writer, "    </tr>")?;
@@ -49,10 +191,10 @@ pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R) -> Result<()> {
     while let Some(row) = rows.next()? {
         writeln!(writer, "    <tr>")?;
 
-        for value in row {
+        for (value, attributes) in row.zip(&attributes) {
             buf.clear();
-            write!(writer, "      <td>")?;
-            write_value(&mut writer, &mut buf, &value?)?;
+            write!(writer, "      <td{}>", attributes)?;
+            write_value(&mut writer, &mut buf, &value?, &opts)?;
             writeln!(writer, "</td>")?;
         }
         writeln!(writer, "    </tr>")?;
@@ -61,5 +203,106 @@ pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R) -> Result<()> {
     writeln!(writer, "  </tbody>")?;
     writeln!(writer, "</table>")?;
 
+    if opts.document {
+        writeln!(writer, "  </body>")?;
+        writeln!(writer, "</html>")?;
+    }
+
+    writer.finish()?;
+
     Ok(())
 }
+
+#[test]
+fn html_document() {
+    let input = r#"{"a":1}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let mut out = vec![];
+    let opts = Options {
+        document: true,
+        title: Some("T & co".into()),
+        ..Default::default()
+    };
+    write(&mut out, reader, opts).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!(
+            "<!DOCTYPE html>\n<html>\n  <head>\n    <meta charset=\"utf-8\">\n    <title>T &amp; co</title>\n{}  </head>\n  <body>\n<table>\n  <thead>\n    <tr>\n      <td>a</td>\n    </tr>\n  </thead>\n  <tbody>\n    <tr>\n      <td>1</td>\n    </tr>\n  </tbody>\n</table>\n  </body>\n</html>\n",
+            STYLESHEET
+        )
+    );
+}
+
+#[test]
+fn html_table_options() {
+    let input = r#"{"id":1,"name":null}
+{"id":2,"name":"b"}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let mut out = vec![];
+    let opts = Options {
+        header_cells: true,
+        table_id: Some("t".into()),
+        table_class: Some("c".into()),
+        caption: Some("<Orders>".into()),
+        null_value: "-".into(),
+        align_numbers: true,
+        ..Default::default()
+    };
+    write(&mut out, reader, opts).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"<table id="t" class="c">
+  <caption>&lt;Orders&gt;</caption>
+  <thead>
+    <tr>
+      <th style="text-align: right">id</th>
+      <th>name</th>
+    </tr>
+  </thead>
+  <tbody>
+    <tr>
+      <td style="text-align: right">1</td>
+      <td>-</td>
+    </tr>
+    <tr>
+      <td style="text-align: right">2</td>
+      <td>b</td>
+    </tr>
+  </tbody>
+</table>
+"#
+    );
+}
+
+#[test]
+fn html_nested() {
+    let value = crate::value!({"a": [1, "<x>"], "b": {"c": null}});
+    let render = |nested: Nested| {
+        let mut out = vec![];
+        let opts = Options {
+            nested,
+            ..Default::default()
+        };
+        write_value(&mut out, &mut String::new(), &value, &opts).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(
+        render(Nested::Json),
+        r#"{"a":[1,"&lt;x&gt;"],"b":{"c":null}}"#
+    );
+    assert_eq!(
+        render(Nested::Table),
+        "<table><tr><th>a</th><td><ul><li>1</li><li>&lt;x&gt;</li></ul></td></tr>\
+         <tr><th>b</th><td><table><tr><th>c</th><td>NULL</td></tr></table></td></tr></table>"
+    );
+    assert_eq!(
+        render(Nested::List),
+        "<dl><dt>a</dt><dd><ul><li>1</li><li>&lt;x&gt;</li></ul></dd>\
+         <dt>b</dt><dd><dl><dt>c</dt><dd>NULL</dd></dl></dd></dl>"
+    );
+}
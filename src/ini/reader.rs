@@ -0,0 +1,209 @@
+use crate::{
+    error::Result,
+    reader::{self, Object, Value},
+};
+
+use std::borrow::Cow;
+
+type Section = (Option<String>, Vec<(String, String)>);
+
+/// Reads every INI section as a record, with the section name stored under
+/// `section_column`. Keys appearing before the first section are returned as
+/// a record without a section name.
+pub struct Reader {
+    index: usize,
+    section_column: String,
+    sections: Vec<Section>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    pub section_column: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            section_column: "section".into(),
+        }
+    }
+}
+
+impl Reader {
+    pub fn new<T: AsRef<[u8]>>(data: T, options: Options) -> Result<Self> {
+        let text = simdutf8::basic::from_utf8(data.as_ref())?;
+        let mut sections: Vec<Section> = vec![];
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = section_name(line) {
+                sections.push((Some(unquote(name)), vec![]));
+                continue;
+            }
+
+            let (key, value) = split_entry(line);
+
+            if sections.is_empty() {
+                sections.push((None, vec![]));
+            }
+
+            sections
+                .last_mut()
+                .unwrap()
+                .1
+                .push((unquote(key.trim()), unquote(value.trim())));
+        }
+
+        Ok(Self {
+            index: 0,
+            section_column: options.section_column,
+            sections,
+        })
+    }
+}
+
+impl reader::ValueReader for Reader {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let Some((name, entries)) = self.sections.get(self.index) else {
+            return Ok(None);
+        };
+
+        let mut row = Object::with_capacity(entries.len() + 1);
+
+        row.insert(
+            Cow::Borrowed(self.section_column.as_str()),
+            name.as_ref()
+                .map(|n| Value::String(Cow::Borrowed(n.as_str())))
+                .unwrap_or(Value::Null),
+        );
+
+        for (key, value) in entries {
+            row.insert(
+                Cow::Borrowed(key.as_str()),
+                reader::parse_bytes(value.as_bytes()),
+            );
+        }
+
+        self.index += 1;
+
+        Ok(Some(Value::Object(row)))
+    }
+}
+
+/// Length of the quoted string `s` starts with, 0 when it isn't quoted.
+fn quoted_len(s: &str) -> usize {
+    match s.strip_prefix('"') {
+        Some(rest) => {
+            let mut escaped = false;
+            rest.find(|c| {
+                let is_end = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                is_end
+            })
+            .map_or(0, |index| index + 2)
+        }
+        None => 0,
+    }
+}
+
+/// Name of a section header, which may be quoted and followed by a comment.
+fn section_name(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix('[')?;
+    let name_start = rest.len() - rest.trim_start().len();
+    let end = name_start + quoted_len(&rest[name_start..]);
+    let end = end + rest[end..].find(']')?;
+
+    let comment = rest[end + 1..].trim_start();
+    if !comment.is_empty() && !comment.starts_with([';', '#']) {
+        return None;
+    }
+
+    Some(rest[..end].trim())
+}
+
+/// Splits an entry at the first `=` or `:` following its key, which may be
+/// quoted.
+fn split_entry(line: &str) -> (&str, &str) {
+    let key_end = quoted_len(line);
+
+    match line[key_end..].find(['=', ':']) {
+        Some(index) => (&line[..key_end + index], &line[key_end + index + 1..]),
+        None => (line, ""),
+    }
+}
+
+fn unquote(s: &str) -> String {
+    let Some(inner) = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .filter(|_| s.len() >= 2)
+    else {
+        return s.to_string();
+    };
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => out.push('\n'),
+            ('\\', Some('r')) => out.push('\r'),
+            ('\\', Some(c @ ('"' | '\\'))) => out.push(c),
+            (c, _) => {
+                out.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+
+    out
+}
+
+#[test]
+fn ini_sections() {
+    use crate::reader::ValueReader;
+
+    let input = "
+; global settings
+debug = true
+
+[web]
+port = 8080
+motd = \"hello; \\\"world\\\"\"
+
+[worker] ; background jobs
+threads: 4
+
+[\"a] b\"]
+x = 1
+";
+
+    let mut reader = Reader::new(input, Default::default()).unwrap();
+
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"section": null, "debug": "true"})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"section": "web", "port": 8080, "motd": "hello; \"world\""})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"section": "worker", "threads": 4})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"section": "a] b", "x": 1})
+    );
+    assert!(reader.next().unwrap().is_none());
+}
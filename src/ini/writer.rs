@@ -0,0 +1,180 @@
+use crate::reader::ValueReader;
+use crate::{Error, Result, Value};
+
+use std::io::Write;
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Column used as the section name, defaults to the first column
+    pub section_column: Option<String>,
+}
+
+/// Values are quoted when they would otherwise not survive a round trip,
+/// i.e. when they contain line breaks, comment characters or surrounding
+/// whitespace.
+pub fn write_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+    let needs_quotes =
+        s.trim() != s || s.starts_with('"') || s.contains(['\n', '\r', ';', '#', '[', ']']);
+
+    if !needs_quotes {
+        writer.write_all(s.as_bytes())?;
+        return Ok(());
+    }
+
+    write_quoted(writer, s)
+}
+
+/// Keys are also quoted when they contain the `=` or `:` separating them
+/// from their value.
+pub fn write_key<W: Write>(writer: &mut W, key: &str) -> Result<()> {
+    if key.contains(['=', ':']) {
+        write_quoted(writer, key)
+    } else {
+        write_string(writer, key)
+    }
+}
+
+fn write_quoted<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+    writer.write_all(b"\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    writer.write_all(b"\"")?;
+
+    Ok(())
+}
+
+pub fn write_value<W: Write>(writer: &mut W, value: &Value) -> Result<()> {
+    match value {
+        Value::Int(i) => write!(writer, "{}", i)?,
+        Value::String(s) => write_string(writer, s)?,
+        Value::Float(f) => write!(writer, "{}", f)?,
+        Value::Bool(b) => write!(writer, "{}", b)?,
+        Value::Object(o) => write_string(writer, &serde_json::to_string(o)?)?,
+        Value::Array(o) => write_string(writer, &serde_json::to_string(o)?)?,
+        Value::Null => {}
+        Value::Binary(b) => write!(writer, "{}", hex::encode(b))?,
+    };
+
+    Ok(())
+}
+
+pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R, opts: Options) -> Result<()> {
+    let mut rows = reader.into_rows()?;
+
+    let section_index = match &opts.section_column {
+        None => 0,
+        Some(name) => rows
+            .fields()
+            .iter()
+            .position(|f| &f.name == name)
+            .ok_or_else(|| Error::OutputError(format!("Column `{}` not found", name)))?,
+    };
+
+    let names = rows
+        .fields()
+        .iter()
+        .map(|f| f.name.clone())
+        .collect::<Vec<_>>();
+
+    let mut is_first = true;
+    let mut section = vec![];
+
+    while let Some(row) = rows.next()? {
+        let values = row.collect::<Result<Vec<_>>>()?;
+        let is_first_record = is_first;
+
+        if is_first {
+            is_first = false;
+        } else {
+            writer.write_all(b"\n")?;
+        }
+
+        // Entries without a section can only come before the first one
+        match &values[section_index] {
+            Value::Null if is_first_record => {}
+            Value::Null => {
+                return Err(Error::OutputError(format!(
+                    "Only the first record can have a null `{}`, it has no section",
+                    names[section_index]
+                )))
+            }
+            value => {
+                section.clear();
+                write_value(&mut section, value)?;
+                writer.write_all(b"[")?;
+                writer.write_all(&section)?;
+                writer.write_all(b"]\n")?;
+            }
+        }
+
+        for (i, (name, value)) in names.iter().zip(&values).enumerate() {
+            if i == section_index || *value == Value::Null {
+                continue;
+            }
+
+            write_key(&mut writer, name)?;
+            writer.write_all(b" = ")?;
+            write_value(&mut writer, value)?;
+            writer.write_all(b"\n")?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ini_write() {
+    use crate::ini::reader::Reader;
+
+    let input = r#"{"name":"web","port":8080,"motd":" hi; there","a=b":1,"c:d":null}
+{"name":"worker","port":null,"motd":"x","a=b":2,"c:d":"e"}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+
+    let mut out = vec![];
+    write(&mut out, reader, Default::default()).unwrap();
+    assert_eq!(
+        String::from_utf8(out.clone()).unwrap(),
+        "[web]\nport = 8080\nmotd = \" hi; there\"\n\"a=b\" = 1\n\n\
+         [worker]\nmotd = x\n\"a=b\" = 2\n\"c:d\" = e\n"
+    );
+
+    let mut reader = Reader::new(out, Default::default()).unwrap();
+    reader.next().unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"section": "worker", "motd": "x", "a=b": 2, "c:d": "e"})
+    );
+}
+
+#[test]
+fn ini_null_section() {
+    let write_input = |input: &'static str| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, Default::default()).map(|_| String::from_utf8(out).unwrap())
+    };
+
+    assert_eq!(
+        write_input(
+            r#"{"section":null,"debug":true}
+{"section":"web","debug":false}"#
+        )
+        .unwrap(),
+        "debug = true\n\n[web]\ndebug = false\n"
+    );
+    assert!(write_input(
+        r#"{"section":"web","debug":false}
+{"section":null,"debug":true}"#
+    )
+    .is_err());
+}
@@ -1,8 +1,26 @@
+use serde::{Deserialize, Deserializer};
 use std::io::{Read, Seek, SeekFrom};
 
-mod line_length_limiter;
+mod encoding_writer;
+mod length_limiter;
 
-pub use line_length_limiter::LineLengthLimiter;
+pub use encoding_writer::{EncodingWriter, Unmappable};
+pub use length_limiter::LengthLimiter;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Encoding(pub &'static encoding_rs::Encoding);
+
+impl<'de> Deserialize<'de> for Encoding {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let str = String::deserialize(deserializer)?;
+        encoding_rs::Encoding::for_label_no_replacement(str.as_bytes())
+            .ok_or_else(|| serde::de::Error::custom(format!("{} is not a valid encoding", str)))
+            .map(Encoding)
+    }
+}
 
 pub struct PartialChunkedFileReader<R> {
     inner: R,
@@ -0,0 +1,198 @@
+use super::Encoding;
+use encoding_rs::EncoderResult;
+use std::io::{Error, ErrorKind, Write};
+
+/// What to write for characters missing from the output encoding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum Unmappable {
+    #[default]
+    #[serde(rename = "error")]
+    Error,
+    /// A question mark
+    #[serde(rename = "replace")]
+    Replace,
+    /// A numeric character reference, like `&#8364;`
+    #[serde(rename = "html_entity")]
+    HtmlEntity,
+    /// A `\uXXXX` escape, what the JSON writer writes instead of entities
+    #[serde(skip)]
+    JsonEscape,
+}
+
+enum Target {
+    Utf8,
+    Utf16 { big_endian: bool },
+    Other(encoding_rs::Encoder),
+}
+
+/// Encodes the UTF-8 written to it in another encoding, passing it through
+/// when the encoding is UTF-8.
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    target: Target,
+    unmappable: Unmappable,
+    /// Start of a character split between two writes
+    pending: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    /// Writes in `encoding`, UTF-8 if not set.
+    pub fn new(inner: W, encoding: Option<&Encoding>, unmappable: Unmappable) -> Self {
+        let encoding = encoding.map_or(encoding_rs::UTF_8, |e| e.0);
+
+        let target = if encoding == encoding_rs::UTF_8 {
+            Target::Utf8
+        } else if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+            // encoding_rs only decodes UTF-16
+            Target::Utf16 {
+                big_endian: encoding == encoding_rs::UTF_16BE,
+            }
+        } else {
+            Target::Other(encoding.new_encoder())
+        };
+
+        EncodingWriter {
+            inner,
+            target,
+            unmappable,
+            pending: vec![],
+            buffer: vec![0; 8192],
+        }
+    }
+
+    fn encode(&mut self, mut input: &str, last: bool) -> std::io::Result<()> {
+        let encoder = match &mut self.target {
+            Target::Utf8 => return self.inner.write_all(input.as_bytes()),
+            Target::Utf16 { big_endian } => {
+                self.buffer.clear();
+                for unit in input.encode_utf16() {
+                    let bytes = if *big_endian {
+                        unit.to_be_bytes()
+                    } else {
+                        unit.to_le_bytes()
+                    };
+                    self.buffer.extend_from_slice(&bytes);
+                }
+                return self.inner.write_all(&self.buffer);
+            }
+            Target::Other(encoder) => encoder,
+        };
+
+        self.buffer.resize(8192, 0);
+
+        loop {
+            let (result, read, written) =
+                encoder.encode_from_utf8_without_replacement(input, &mut self.buffer, last);
+            self.inner.write_all(&self.buffer[..written])?;
+            input = &input[read..];
+
+            match (result, self.unmappable) {
+                (EncoderResult::InputEmpty, _) => return Ok(()),
+                (EncoderResult::OutputFull, _) => {}
+                (EncoderResult::Unmappable(c), Unmappable::Error) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("`{}` can't be encoded in {}", c, encoder.encoding().name()),
+                    ))
+                }
+                (EncoderResult::Unmappable(_), Unmappable::Replace) => {
+                    self.inner.write_all(b"?")?
+                }
+                (EncoderResult::Unmappable(c), Unmappable::HtmlEntity) => {
+                    write!(self.inner, "&#{};", c as u32)?
+                }
+                (EncoderResult::Unmappable(c), Unmappable::JsonEscape) => {
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        write!(self.inner, "\\u{:04x}", unit)?
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flushes the encoder state and returns the inner writer.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if !self.pending.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Incomplete UTF-8 character",
+            ));
+        }
+
+        self.encode("", true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Target::Utf8 = self.target {
+            return self.inner.write(buf);
+        }
+
+        let mut input = buf;
+
+        // Complete the character split by the last write
+        while !self.pending.is_empty() && !input.is_empty() {
+            self.pending.push(input[0]);
+            input = &input[1..];
+
+            match std::str::from_utf8(&self.pending) {
+                Ok(c) => {
+                    let mut c_buf = [0; 4];
+                    let c = c.chars().next().unwrap().encode_utf8(&mut c_buf);
+                    self.pending.clear();
+                    self.encode(c, false)?;
+                }
+                Err(e) if e.error_len().is_none() => {}
+                Err(e) => return Err(Error::new(ErrorKind::InvalidData, e)),
+            }
+        }
+
+        let valid_up_to = match std::str::from_utf8(input) {
+            Ok(_) => input.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(Error::new(ErrorKind::InvalidData, e)),
+        };
+
+        // Checked above
+        let valid = unsafe { std::str::from_utf8_unchecked(&input[..valid_up_to]) };
+        self.encode(valid, false)?;
+        self.pending.extend_from_slice(&input[valid_up_to..]);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[test]
+fn encoding_writer() {
+    let encode = |encoding: &'static encoding_rs::Encoding, unmappable: Unmappable| {
+        let mut writer = EncodingWriter::new(vec![], Some(&Encoding(encoding)), unmappable);
+        // Split in the middle of `é`
+        let input = "café €1 ☃".as_bytes();
+        writer.write_all(&input[..4])?;
+        writer.write_all(&input[4..5])?;
+        writer.write_all(&input[5..])?;
+        writer.finish()
+    };
+
+    assert_eq!(
+        encode(encoding_rs::WINDOWS_1252, Unmappable::Replace).unwrap(),
+        b"caf\xe9 \x801 ?"
+    );
+    assert_eq!(
+        encode(encoding_rs::SHIFT_JIS, Unmappable::HtmlEntity).unwrap(),
+        b"caf&#233; &#8364;1 &#9731;"
+    );
+    assert!(encode(encoding_rs::WINDOWS_1252, Unmappable::Error).is_err());
+    assert_eq!(
+        encode(encoding_rs::UTF_16LE, Unmappable::Error).unwrap(),
+        b"c\0a\0f\0\xe9\0 \0\xac\x201\0 \0\x03\x26"
+    );
+}
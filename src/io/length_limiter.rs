@@ -0,0 +1,83 @@
+use crate::error::{Error, Limit};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Errors on lines longer than a limit, or on inputs longer than it when
+/// reading a single record.
+pub struct LengthLimiter<R> {
+    inner: R,
+    current_length: usize,
+    limit: usize,
+    /// [`Limit::RecordLength`] to count all the bytes read instead of lines
+    kind: Limit,
+}
+
+impl<R: Read + Seek> LengthLimiter<R> {
+    /// Errors on lines longer than `limit` bytes, if any.
+    pub fn lines(inner: R, limit: Option<usize>) -> Self {
+        LengthLimiter {
+            inner,
+            limit: limit.unwrap_or(usize::MAX),
+            current_length: 0,
+            kind: Limit::LineLength,
+        }
+    }
+}
+
+impl<R: Read> LengthLimiter<R> {
+    /// Errors once more than `limit` bytes are read, for a single record.
+    pub fn record(inner: R, limit: Option<usize>) -> Self {
+        LengthLimiter {
+            inner,
+            limit: limit.unwrap_or(usize::MAX),
+            current_length: 0,
+            kind: Limit::RecordLength,
+        }
+    }
+}
+
+impl<R> LengthLimiter<R> {
+    fn check(&self, length: usize) -> std::io::Result<()> {
+        if length > self.limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                Error::limit(self.kind, self.limit as u64),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for LengthLimiter<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::result::Result<usize, std::io::Error> {
+        let bytes_read = self.inner.read(buf)?;
+        let mut line_start = 0;
+
+        if self.kind == Limit::RecordLength {
+            self.current_length += bytes_read;
+            self.check(self.current_length)?;
+            return Ok(bytes_read);
+        }
+
+        for (index, _) in buf[..bytes_read]
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| b == b'\n')
+        {
+            self.check(self.current_length + index - line_start)?;
+            self.current_length = 0;
+            line_start = index + 1;
+        }
+
+        self.current_length += bytes_read - line_start;
+        self.check(self.current_length)?;
+
+        Ok(bytes_read)
+    }
+}
+
+impl<R: Seek> Seek for LengthLimiter<R> {
+    fn seek(&mut self, from: SeekFrom) -> std::result::Result<u64, std::io::Error> {
+        self.inner.seek(from)
+    }
+}
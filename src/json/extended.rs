@@ -0,0 +1,124 @@
+use crate::reader::{Object, Value};
+
+use std::borrow::Cow;
+
+/// Replaces MongoDB extended JSON wrappers such as `{"$oid": "..."}` or
+/// `{"$numberLong": "..."}` with their native value. Object ids become
+/// strings, dates milliseconds since the Unix epoch and decimals are kept as
+/// strings, like the BSON reader does.
+pub fn from_extended_json(value: Value<'_>) -> Value<'_> {
+    match value {
+        Value::Array(values) => Value::Array(values.into_iter().map(from_extended_json).collect()),
+        Value::Object(object) => {
+            let is_wrapper = matches!(object.keys().next(), Some(k) if k.starts_with('$'));
+
+            if is_wrapper {
+                if let Some(value) = unwrap(&object) {
+                    return value;
+                }
+            }
+
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, from_extended_json(value)))
+                    .collect(),
+            )
+        }
+        other => other,
+    }
+}
+
+fn string<'a>(value: Option<&Value<'a>>) -> Option<Cow<'a, str>> {
+    match value {
+        Some(Value::String(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// Milliseconds since the Unix epoch of a `$date`, given either as such or
+/// as an ISO 8601 string in relaxed mode.
+fn date<'a>(value: &Value<'a>) -> Option<Value<'a>> {
+    let millis = match value {
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()?
+            .timestamp_millis(),
+        Value::Int(millis) => *millis,
+        Value::Object(object) => unwrap(object)?.as_int()?,
+        _ => return None,
+    };
+
+    Some(Value::Int(millis))
+}
+
+fn base64<'a>(value: Option<&Value<'a>>) -> Option<Value<'a>> {
+    let binary = bson::Binary::from_base64(string(value)?, None).ok()?;
+    Some(Value::Binary(binary.bytes))
+}
+
+fn unwrap<'a>(object: &Object<'a>) -> Option<Value<'a>> {
+    let (key, value) = object.get_index(0)?;
+
+    match (key.as_ref(), object.len()) {
+        ("$oid" | "$symbol" | "$code", 1) => string(Some(value)).map(Value::String),
+        ("$code", 2) if object.contains_key("$scope") => string(Some(value)).map(Value::String),
+        ("$numberInt" | "$numberLong", 1) => string(Some(value))?.parse().ok().map(Value::Int),
+        ("$numberDouble", 1) => string(Some(value))?.parse().ok().map(Value::Float),
+        ("$numberDecimal", 1) => string(Some(value)).map(Value::String),
+        ("$date", 1) => date(value),
+        ("$binary", 1) => {
+            let inner = value.as_object()?;
+            base64(inner.get("base64"))
+        }
+        ("$binary" | "$type", 2) => base64(object.get("$binary")),
+        ("$timestamp", 1) => value.as_object()?.get("t")?.as_int().map(Value::Int),
+        ("$regularExpression", 1) => {
+            let inner = value.as_object()?;
+            Some(Value::String(Cow::Owned(format!(
+                "/{}/{}",
+                string(inner.get("pattern"))?,
+                string(inner.get("options")).unwrap_or_default()
+            ))))
+        }
+        ("$regex" | "$options", 2) => Some(Value::String(Cow::Owned(format!(
+            "/{}/{}",
+            string(object.get("$regex"))?,
+            string(object.get("$options")).unwrap_or_default()
+        )))),
+        ("$undefined" | "$minKey" | "$maxKey", 1) => Some(Value::Null),
+        _ => None,
+    }
+}
+
+#[test]
+fn extended_json() {
+    let value = crate::value!({
+        "_id": {"$oid": "5f5e1a6b9c1d2e3f4a5b6c7d"},
+        "count": {"$numberLong": "9007199254740993"},
+        "ratio": {"$numberDouble": "0.5"},
+        "price": {"$numberDecimal": "1.10"},
+        "created": {"$date": {"$numberLong": "1600000000000"}},
+        "updated": {"$date": "2020-09-13T12:26:40.5Z"},
+        "data": {"$binary": {"base64": "yv4=", "subType": "00"}},
+        "tags": [{"$numberInt": "1"}, {"plain": {"$minKey": 1}}],
+        "$notAWrapper": {"$oid": 1}
+    });
+
+    let mut expected = crate::value!({
+        "_id": "5f5e1a6b9c1d2e3f4a5b6c7d",
+        "count": 9007199254740993,
+        "ratio": 0.5,
+        "price": "1.10",
+        "created": 1600000000000,
+        "updated": 1600000000500,
+        "data": null,
+        "tags": [1, {"plain": null}],
+        "$notAWrapper": {"$oid": 1}
+    });
+
+    if let Value::Object(object) = &mut expected {
+        object.insert("data".into(), Value::Binary(vec![0xca, 0xfe]));
+    }
+
+    assert_eq!(from_extended_json(value), expected);
+}
@@ -1,2 +1,3 @@
+pub mod extended;
 pub mod reader;
 pub mod writer;
@@ -1,5 +1,7 @@
 use crate::{
     error::Error,
+    io::LengthLimiter,
+    json::extended::from_extended_json,
     reader::{Value, ValueReader},
     Result,
 };
@@ -11,6 +13,8 @@ pub struct RecordReader<R: AsRef<[u8]>> {
     reader: R,
     expect_end: bool,
     flatten_objects: bool,
+    extended_json: bool,
+    max_record_length: Option<usize>,
 }
 
 #[derive(Debug, Default, serde::Deserialize)]
@@ -26,17 +30,25 @@ pub struct Options {
     #[serde(default)]
     pub mode: JsonMode,
     pub flatten_objects: bool,
+    /// Interpret MongoDB extended JSON wrappers (`$oid`, `$date`, `$numberLong`, ...)
+    #[serde(default)]
+    pub extended_json: bool,
+    /// Longest record allowed in bytes, or NDJSON line, no limit when not set
+    #[serde(default)]
+    pub max_record_length: Option<usize>,
 }
 
+/// Reads the next record, erroring once it goes over `max_length` bytes.
 pub fn read_value<'a, T: Deserialize<'a>>(
     reader: &mut &'a [u8],
     expect_end: &mut bool,
+    max_length: Option<usize>,
 ) -> Result<Option<T>> {
     if *expect_end {
         match read_skipping_ws(reader) {
             Some(b',') => {
                 *reader = &reader[1..];
-                deserialize_single(reader).map(Some)
+                deserialize_single(reader, max_length).map(Some)
             }
             Some(b']') => {
                 *reader = &reader[1..];
@@ -44,7 +56,7 @@ pub fn read_value<'a, T: Deserialize<'a>>(
                 match read_skipping_ws(reader) {
                     Some(_) => {
                         *expect_end = false;
-                        deserialize_single(reader).map(Some)
+                        deserialize_single(reader, max_length).map(Some)
                     }
                     None => Ok(None),
                 }
@@ -72,12 +84,12 @@ pub fn read_value<'a, T: Deserialize<'a>>(
                     // Some(other) => todo!("{}", other),
                     Some(_other) => {
                         *expect_end = true;
-                        deserialize_single(reader).map(Some)
+                        deserialize_single(reader, max_length).map(Some)
                     }
                     None => Err(invalid_data("Expected a JSON value, found end of stream")),
                 }
             }
-            Some(_) => deserialize_single(reader).map(Some),
+            Some(_) => deserialize_single(reader, max_length).map(Some),
             None => Ok(None),
         }
     }
@@ -87,8 +99,16 @@ impl<R: AsRef<[u8]> + Send + Sync> ValueReader for RecordReader<R> {
     fn next(&mut self) -> Result<Option<Value<'_>>> {
         let mut slice = &self.reader.as_ref()[self.offset..];
 
-        let value = match read_value::<Value>(&mut slice, &mut self.expect_end) {
+        let value = read_value::<Value>(&mut slice, &mut self.expect_end, self.max_record_length);
+
+        let value = match value {
             Ok(Some(value)) => {
+                let value = if self.extended_json {
+                    from_extended_json(value)
+                } else {
+                    value
+                };
+
                 if self.flatten_objects {
                     Ok(Some(value.flatten_object()))
                 } else {
@@ -115,6 +135,8 @@ impl<R: AsRef<[u8]> + Send + Sync + 'static> RecordReader<R> {
             offset: 0,
             reader: inner,
             flatten_objects: opts.flatten_objects,
+            extended_json: opts.extended_json,
+            max_record_length: opts.max_record_length,
             expect_end: false,
         })
     }
@@ -134,10 +156,43 @@ fn invalid_data(msg: &str) -> Error {
     Error::CustomError(msg.to_string())
 }
 
-fn deserialize_single<'de, T: Deserialize<'de>>(reader: &mut &'de [u8]) -> Result<T> {
-    let mut de = serde_json::Deserializer::from_reader(reader);
+fn deserialize_single<'de, T: Deserialize<'de>>(
+    reader: &mut &'de [u8],
+    max_length: Option<usize>,
+) -> Result<T> {
+    let mut de = serde_json::Deserializer::from_reader(LengthLimiter::record(reader, max_length));
 
     de.disable_recursion_limit();
 
-    T::deserialize(&mut de).map_err(|e| Error::CustomError(format!("{}", e)))
+    T::deserialize(&mut de).map_err(|e| {
+        if e.is_io() {
+            std::io::Error::from(e).into()
+        } else {
+            Error::CustomError(format!("{}", e))
+        }
+    })
+}
+
+#[test]
+fn json_record_length() {
+    let read = |input: &'static str| {
+        let opts = Options {
+            max_record_length: Some(12),
+            ..Default::default()
+        };
+        let mut reader = RecordReader::new(input.as_bytes(), opts)?;
+        while reader.next()?.is_some() {}
+        Result::Ok(())
+    };
+
+    assert!(read("{\"a\":1}\n{\"a\":\"bc\"}\n").is_ok());
+    assert!(read("[{\"a\":1},{\"a\":\"bc\"}]").is_ok());
+    assert!(matches!(
+        read("{\"a\":1}\n{\"a\":\"bcdefgh\"}\n"),
+        Err(Error::LimitExceeded(_))
+    ));
+    assert!(matches!(
+        read("[{\"a\":1},\n{\"a\":\n[1, 2, 3, 4, 5]}]"),
+        Err(Error::LimitExceeded(_))
+    ));
 }
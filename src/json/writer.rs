@@ -1,3 +1,4 @@
+use crate::io::{Encoding, EncodingWriter, Unmappable};
 use crate::reader::ValueReader;
 use crate::Result;
 
@@ -17,9 +18,30 @@ pub enum Mode {
 #[derive(Debug, serde::Deserialize)]
 pub struct Options {
     pub mode: Mode,
+    /// Output encoding, UTF-8 when not set
+    #[serde(default)]
+    pub encoding: Option<Encoding>,
+    #[serde(default)]
+    pub unmappable: Unmappable,
 }
 
-pub fn write<W: Write, R: ValueReader>(mut inner: W, mut reader: R, opts: Options) -> Result<()> {
+pub fn write<W: Write, R: ValueReader>(inner: W, reader: R, opts: Options) -> Result<()> {
+    // Entities would end up as text in the strings, escapes decode to the characters
+    let unmappable = match opts.unmappable {
+        Unmappable::HtmlEntity => Unmappable::JsonEscape,
+        unmappable => unmappable,
+    };
+    let writer = EncodingWriter::new(inner, opts.encoding.as_ref(), unmappable);
+    write_records(writer, reader, &opts)?.finish()?;
+
+    Ok(())
+}
+
+fn write_records<W: Write, R: ValueReader>(
+    mut inner: W,
+    mut reader: R,
+    opts: &Options,
+) -> Result<W> {
     match opts.mode {
         Mode::ArrayPretty => {
             inner.write_all(b"[\n")?;
@@ -70,5 +92,60 @@ pub fn write<W: Write, R: ValueReader>(mut inner: W, mut reader: R, opts: Option
         }
     };
 
-    Ok(())
+    Ok(inner)
+}
+
+#[test]
+fn json_encoding() {
+    let reader = crate::json::reader::RecordReader::new(
+        r#"{"name":"Café ☃"}"#.as_bytes(),
+        Default::default(),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    let opts = Options {
+        mode: Mode::NdJson,
+        encoding: Some(Encoding(encoding_rs::WINDOWS_1252)),
+        unmappable: Unmappable::Replace,
+    };
+    write(&mut out, reader, opts).unwrap();
+
+    assert_eq!(out, b"{\"name\":\"Caf\xe9 ?\"}\n");
+
+    let reader = crate::json::reader::RecordReader::new(
+        r#"{"name":"Café ☃ 😀"}"#.as_bytes(),
+        Default::default(),
+    )
+    .unwrap();
+
+    let mut out = vec![];
+    let opts = Options {
+        mode: Mode::NdJson,
+        encoding: Some(Encoding(encoding_rs::WINDOWS_1252)),
+        unmappable: Unmappable::HtmlEntity,
+    };
+    write(&mut out, reader, opts).unwrap();
+
+    assert_eq!(out, b"{\"name\":\"Caf\xe9 \\u2603 \\ud83d\\ude00\"}\n");
+}
+
+#[test]
+fn json_binary() {
+    let input = r#"{"data":{"$binary":{"base64":"yv4=","subType":"00"}}}"#;
+    let opts = crate::json::reader::Options {
+        extended_json: true,
+        ..Default::default()
+    };
+    let reader = crate::json::reader::RecordReader::new(input.as_bytes(), opts).unwrap();
+
+    let mut out = vec![];
+    let opts = Options {
+        mode: Mode::NdJson,
+        encoding: None,
+        unmappable: Default::default(),
+    };
+    write(&mut out, reader, opts).unwrap();
+
+    assert_eq!(out, b"{\"data\":\"cafe\"}\n");
 }
@@ -1,19 +1,31 @@
 pub mod arrow;
 pub mod avro;
+pub mod bson;
+pub mod cbor;
 pub mod csv;
 pub mod deserializer;
 pub mod erlang;
 pub mod error;
 pub mod excel;
+pub mod fixed_width;
 pub mod flatten;
 pub mod html;
+pub mod ini;
 pub mod io;
 pub mod json;
+pub mod markdown;
+pub mod msgpack;
+pub mod normalize;
 pub mod reader;
 pub mod sql;
+pub mod sqlite;
+pub mod toml;
 pub mod util;
 pub mod xml;
+pub mod yaml;
 
+pub use crate::erlang::InputOptions;
+pub use crate::erlang::Limits;
 pub use crate::erlang::ReaderOptions;
 pub use crate::erlang::WriterOptions;
 pub use crate::error::Error;
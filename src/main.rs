@@ -1,9 +1,13 @@
-use kon::{avro, csv, html, json, sql, xml, Error, ReaderOptions, WriterOptions};
+use kon::{
+    avro, bson, cbor, csv, fixed_width, html, ini, json, markdown, msgpack, sql, sqlite, toml, xml,
+    yaml, Error, InputOptions, WriterOptions,
+};
 
 use serde_json::json;
 use std::fs::File;
+use std::path::Path;
 
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 
 fn convert() -> Result<(), Error> {
     let args: Vec<String> = std::env::args().collect();
@@ -16,20 +20,40 @@ fn convert() -> Result<(), Error> {
                 ));
             }
 
-            let input_options: ReaderOptions = serde_json::from_str(&args[3])?;
-            let reader = kon::reader::new_reader(&args[2], input_options)?;
+            let input_options: InputOptions = serde_json::from_str(&args[3])?;
+            let reader = kon::reader::new_limited_reader(
+                &args[2],
+                input_options.reader,
+                input_options.limits,
+            )?;
 
             let output_file = File::create(&args[4])?;
             let writer = BufWriter::new(output_file);
             let output_options: WriterOptions = serde_json::from_str(&args[5])?;
 
             match output_options {
+                WriterOptions::Csv(opts) if opts.normalize.is_some() => {
+                    drop(writer);
+                    write_csv_tables(&args[4], reader, opts)
+                }
                 WriterOptions::Csv(opts) => csv::writer::write(writer, reader, opts),
                 WriterOptions::Json(opts) => json::writer::write(writer, reader, opts),
                 WriterOptions::Sql(opts) => sql::writer::write(writer, reader, opts),
-                WriterOptions::Html {} => html::writer::write(writer, reader),
-                WriterOptions::Xml {} => xml::writer::write(writer, reader),
+                WriterOptions::Html(opts) => html::writer::write(writer, reader, opts),
+                WriterOptions::Xml(opts) => xml::writer::write(writer, reader, opts),
                 WriterOptions::Avro(opts) => avro::writer::write(writer, reader, opts),
+                WriterOptions::Markdown(opts) => markdown::writer::write(writer, reader, opts),
+                WriterOptions::Yaml(opts) => yaml::writer::write(writer, reader, opts),
+                WriterOptions::Toml(opts) => toml::writer::write(writer, reader, opts),
+                WriterOptions::Ini(opts) => ini::writer::write(writer, reader, opts),
+                WriterOptions::MsgPack(opts) => msgpack::writer::write(writer, reader, opts),
+                WriterOptions::Cbor(opts) => cbor::writer::write(writer, reader, opts),
+                WriterOptions::Bson(opts) => bson::writer::write(writer, reader, opts),
+                WriterOptions::FixedWidth(opts) => fixed_width::writer::write(writer, reader, opts),
+                WriterOptions::SqliteDb(opts) => {
+                    drop(writer);
+                    sqlite::writer::write(&args[4], reader, opts)
+                }
             }?;
         }
 
@@ -43,6 +67,31 @@ fn convert() -> Result<(), Error> {
     Ok(())
 }
 
+/// Writes the root table to `output` and each child table next to it, named
+/// after the table.
+fn write_csv_tables<R: kon::reader::ValueReader>(
+    output: &str,
+    reader: R,
+    opts: csv::writer::Options,
+) -> Result<(), Error> {
+    let output = Path::new(output);
+    let root_name = output
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("data")
+        .to_string();
+
+    csv::writer::write_tables(reader, &root_name, opts, |name| {
+        let path = if name == root_name {
+            output.to_path_buf()
+        } else {
+            output.with_file_name(format!("{}.csv", name))
+        };
+
+        Ok(Box::new(BufWriter::new(File::create(path)?)) as Box<dyn Write>)
+    })
+}
+
 fn main() {
     match convert() {
         Err(e) => {
@@ -53,6 +102,7 @@ fn main() {
                     Error::CsvError(_) => json!(["parse_error", format!("{}", e)]),
                     Error::InputError(_) => json!(["parse_error", format!("{}", e)]),
                     Error::OutputError(_) => json!(["parse_error", format!("{}", e)]),
+                    Error::LimitExceeded(limit) => json!(["limit_exceeded", limit]),
                     _ => json!(format!("error: {}", e)),
                 }
             );
@@ -0,0 +1,330 @@
+use crate::{
+    error::{Error, Result},
+    reader::{self, Object, Value},
+};
+
+use std::borrow::Cow;
+
+/// Reads GitHub-flavored Markdown tables as well as ASCII and box-drawing
+/// tables such as the ones printed by `psql` and the `mysql` client.
+pub struct Reader {
+    index: usize,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    pub has_headers: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { has_headers: true }
+    }
+}
+
+impl Reader {
+    pub fn new<T: AsRef<[u8]>>(data: T, options: Options) -> Result<Self> {
+        let text = simdutf8::basic::from_utf8(data.as_ref())?;
+
+        let mut lines = vec![];
+        // Positions of the `+` of the first ASCII border, the `|` found under
+        // them separate the cells
+        let mut junctions: Option<Vec<usize>> = None;
+        let mut header_separated = false;
+
+        for line in text.lines().map(str::trim_end) {
+            if line.trim().is_empty() || is_footer(line.trim()) {
+                continue;
+            }
+
+            if is_border(line) {
+                let plus = line
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '+')
+                    .map(|(i, _)| i)
+                    .collect::<Vec<_>>();
+
+                if !plus.is_empty() {
+                    junctions.get_or_insert(plus);
+                    continue;
+                }
+
+                // Only the line under the header separates, as in `|---|---|`,
+                // the later ones are rows of dashes
+                if line.chars().any(is_box_junction) {
+                    continue;
+                } else if lines.len() == 1 && !header_separated {
+                    header_separated = true;
+                    continue;
+                }
+            }
+
+            lines.push(line);
+        }
+
+        let has_pipes = lines
+            .iter()
+            .any(|line| line.contains('|') || line.contains(BOX_VERTICALS));
+
+        let mut rows = lines
+            .iter()
+            .filter(|line| !has_pipes || line.contains('|') || line.contains(BOX_VERTICALS))
+            .map(|line| {
+                if line.contains(BOX_VERTICALS) {
+                    split_box_cells(line)
+                } else {
+                    junctions
+                        .as_ref()
+                        .and_then(|junctions| split_at_junctions(line, junctions))
+                        .unwrap_or_else(|| split_cells(line.trim()))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let headers: Vec<String> = if options.has_headers && !rows.is_empty() {
+            rows.remove(0)
+                .into_iter()
+                .enumerate()
+                .map(|(i, h)| {
+                    if h.is_empty() {
+                        format!("column_{}", i + 1)
+                    } else {
+                        h
+                    }
+                })
+                .collect()
+        } else {
+            let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+            (0..width).map(|i| format!("column_{}", i + 1)).collect()
+        };
+
+        if let Some((index, row)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() > headers.len())
+        {
+            return Err(Error::InputError(format!(
+                "Row {} has {} cells but the table has {} columns, escape the `|` in cells as `\\|`",
+                index + 1,
+                row.len(),
+                headers.len()
+            )));
+        }
+
+        Ok(Self {
+            index: 0,
+            headers,
+            rows,
+        })
+    }
+}
+
+impl reader::ValueReader for Reader {
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let Some(cells) = self.rows.get(self.index) else {
+            return Ok(None);
+        };
+
+        // Missing cells at the end of the row are nulls
+        let row = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let value = cells
+                    .get(i)
+                    .map_or(Value::Null, |s| reader::parse_bytes(s.as_bytes()));
+                (Cow::Borrowed(h.as_str()), value)
+            })
+            .collect::<Object>();
+
+        self.index += 1;
+
+        Ok(Some(Value::Object(row)))
+    }
+}
+
+/// Separator lines such as `|---|:--:|`, `----+----`, `+----+` or `├───┼───┤`.
+fn is_border(line: &str) -> bool {
+    line.chars()
+        .any(|c| matches!(c, '-' | '=' | '─' | '━' | '═'))
+        && line.chars().all(|c| {
+            matches!(
+                c,
+                ' ' | '-'
+                    | '='
+                    | '+'
+                    | ':'
+                    | '|'
+                    | '─'
+                    | '━'
+                    | '═'
+                    | '┌'
+                    | '┐'
+                    | '└'
+                    | '┘'
+                    | '├'
+                    | '┤'
+                    | '┬'
+                    | '┴'
+                    | '┼'
+                    | '╔'
+                    | '╗'
+                    | '╚'
+                    | '╝'
+                    | '╠'
+                    | '╣'
+                    | '╦'
+                    | '╩'
+                    | '╬'
+                    | '╞'
+                    | '╡'
+                    | '╪'
+                    | '╤'
+                    | '╧'
+            )
+        })
+}
+
+const BOX_VERTICALS: [char; 3] = ['│', '┃', '║'];
+
+fn is_box_junction(c: char) -> bool {
+    !matches!(c, ' ' | '-' | '=' | '+' | ':' | '|' | '─' | '━' | '═')
+}
+
+/// Row count footers printed by `psql` (`(3 rows)`) and `mysql` (`3 rows in set`).
+fn is_footer(line: &str) -> bool {
+    let line = line.strip_prefix('(').unwrap_or(line);
+    let digits = line
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(line.len());
+
+    (digits > 0 && line[digits..].starts_with(" row")) || line.starts_with("Empty set")
+}
+
+/// Splits a row of a box-drawing table, where `|` is part of the cells.
+fn split_box_cells(line: &str) -> Vec<String> {
+    line.trim()
+        .trim_matches(BOX_VERTICALS)
+        .split(BOX_VERTICALS)
+        .map(|c| c.trim().to_string())
+        .collect()
+}
+
+/// Splits a row of an ASCII table at the `|` under the `+` of its border,
+/// keeping the other `|` in the cells, or returns `None` when they don't line
+/// up.
+fn split_at_junctions(line: &str, junctions: &[usize]) -> Option<Vec<String>> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut cells = vec![];
+    let mut start = 0;
+
+    for &junction in junctions {
+        match chars.get(junction) {
+            Some('|') => {
+                cells.push(&chars[start..junction]);
+                start = junction + 1;
+            }
+            None if junction == chars.len() => {}
+            _ => return None,
+        }
+    }
+
+    cells.push(&chars[start.min(chars.len())..]);
+
+    // Outer borders of `mysql` tables
+    if junctions.first() == Some(&0) {
+        cells.remove(0);
+    }
+    if cells.len() > 1 && matches!(cells.last(), Some(c) if c.is_empty()) {
+        cells.pop();
+    }
+
+    Some(
+        cells
+            .into_iter()
+            .map(|c| c.iter().collect::<String>().trim().to_string())
+            .collect(),
+    )
+}
+
+fn split_cells(line: &str) -> Vec<String> {
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => line,
+    };
+
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+
+    cells.push(cell);
+
+    cells
+        .into_iter()
+        .map(|c| c.trim().replace("<br>", "\n"))
+        .collect()
+}
+
+#[test]
+fn markdown_tables() {
+    use crate::reader::ValueReader;
+
+    let inputs = [
+        "| id | name |\n|---:|:-----|\n| 1 | a \\| b |\n| 2 | c |\n",
+        " id | name\n----+------\n  1 | a | b\n  2 | c\n(2 rows)\n",
+        "+----+-------+\n| id | name  |\n+----+-------+\n|  1 | a | b |\n|  2 | c     |\n+----+-------+\n2 rows in set (0.00 sec)\n",
+        "┌────┬──────┐\n│ id │ name │\n├────┼──────┤\n│  1 │ a | b│\n│  2 │ c    │\n└────┴──────┘\n",
+    ];
+
+    for input in inputs {
+        let mut reader = Reader::new(input, Default::default()).unwrap();
+
+        assert_eq!(reader.headers, vec!["id", "name"]);
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"id": 1, "name": "a | b"})
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"id": 2, "name": "c"})
+        );
+        assert!(reader.next().unwrap().is_none());
+    }
+
+    let mut reader = Reader::new(
+        "| a | b |\n|---|---|\n| - | - |\n| 1 |\n",
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"a": "-", "b": "-"})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"a": 1, "b": null})
+    );
+
+    assert!(Reader::new("| a | b |\n|---|---|\n| 1 | x | y |\n", Default::default()).is_err());
+}
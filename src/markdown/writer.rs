@@ -0,0 +1,165 @@
+use crate::io::{Encoding, EncodingWriter, Unmappable};
+use crate::reader::ValueReader;
+use crate::{DataType, Result, Value};
+use std::io::Write;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    pub pad_columns: bool,
+    pub limit: Option<usize>,
+    /// Output encoding, UTF-8 when not set
+    pub encoding: Option<Encoding>,
+    pub unmappable: Unmappable,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            pad_columns: true,
+            limit: None,
+            encoding: None,
+            unmappable: Unmappable::default(),
+        }
+    }
+}
+
+pub fn write_value(buf: &mut String, value: &Value) -> Result<()> {
+    match value {
+        Value::Int(i) => buf.push_str(&i.to_string()),
+        Value::String(s) => escape(buf, s),
+        Value::Float(s) => buf.push_str(&s.to_string()),
+        Value::Bool(s) => buf.push_str(&s.to_string()),
+        Value::Object(o) => escape(buf, &serde_json::to_string(o)?),
+        Value::Array(o) => escape(buf, &serde_json::to_string(o)?),
+        Value::Null => {}
+        Value::Binary(b) => buf.push_str(&hex::encode(b)),
+    };
+
+    Ok(())
+}
+
+fn escape(buf: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '|' => buf.push_str("\\|"),
+            '\r' => {}
+            '\n' => buf.push_str("<br>"),
+            c => buf.push(c),
+        }
+    }
+}
+
+fn is_numeric(dt: &DataType) -> bool {
+    matches!(dt, DataType::Int | DataType::Float)
+}
+
+fn write_row<W: Write>(
+    writer: &mut W,
+    cells: &[String],
+    widths: &[usize],
+    right_align: &[bool],
+) -> Result<()> {
+    writer.write_all(b"|")?;
+
+    for ((cell, width), right) in cells.iter().zip(widths).zip(right_align) {
+        let padding = width.saturating_sub(cell.chars().count());
+
+        if *right {
+            write!(writer, " {}{} |", " ".repeat(padding), cell)?;
+        } else {
+            write!(writer, " {}{} |", cell, " ".repeat(padding))?;
+        }
+    }
+
+    writer.write_all(b"\n")?;
+
+    Ok(())
+}
+
+pub fn write<W: Write, R: ValueReader>(writer: W, reader: R, opts: Options) -> Result<()> {
+    let mut writer = EncodingWriter::new(writer, opts.encoding.as_ref(), opts.unmappable);
+    let mut rows = reader.into_rows()?;
+    let limit = opts.limit.unwrap_or(usize::MAX);
+
+    let headers = rows
+        .fields()
+        .iter()
+        .map(|f| {
+            let mut buf = String::new();
+            escape(&mut buf, &f.name);
+            buf
+        })
+        .collect::<Vec<_>>();
+
+    let right_align = rows
+        .fields()
+        .iter()
+        .map(|f| is_numeric(&f.data_type))
+        .collect::<Vec<_>>();
+
+    let mut widths = vec![3; headers.len()];
+
+    if opts.pad_columns {
+        for (width, header) in widths.iter_mut().zip(&headers) {
+            *width = (*width).max(header.chars().count());
+        }
+
+        let mut count = 0;
+        let mut buf = String::new();
+
+        while count < limit {
+            let Some(row) = rows.next()? else {
+                break;
+            };
+
+            for (width, value) in widths.iter_mut().zip(row) {
+                buf.clear();
+                write_value(&mut buf, &value?)?;
+                *width = (*width).max(buf.chars().count());
+            }
+
+            count += 1;
+        }
+
+        rows.reset();
+    }
+
+    let no_padding = vec![0; headers.len()];
+    let cell_widths = if opts.pad_columns {
+        &widths
+    } else {
+        &no_padding
+    };
+
+    write_row(&mut writer, &headers, cell_widths, &right_align)?;
+
+    writer.write_all(b"|")?;
+    for (width, right) in widths.iter().zip(&right_align) {
+        let dashes = "-".repeat(if *right { width - 1 } else { *width });
+        write!(writer, " {}{} |", dashes, if *right { ":" } else { "" })?;
+    }
+    writer.write_all(b"\n")?;
+
+    let mut count = 0;
+    let mut cells = vec![String::new(); headers.len()];
+
+    while count < limit {
+        let Some(row) = rows.next()? else {
+            break;
+        };
+
+        for (cell, value) in cells.iter_mut().zip(row) {
+            cell.clear();
+            write_value(cell, &value?)?;
+        }
+
+        write_row(&mut writer, &cells, cell_widths, &right_align)?;
+
+        count += 1;
+    }
+
+    writer.finish()?;
+
+    Ok(())
+}
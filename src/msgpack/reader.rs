@@ -0,0 +1,157 @@
+use crate::{
+    reader::{Object, Value, ValueReader},
+    Error, Result,
+};
+
+use std::borrow::Cow;
+
+/// Reads a stream of concatenated MessagePack values, each one being a record.
+pub struct Reader<R: AsRef<[u8]>> {
+    offset: usize,
+    reader: R,
+    mode: Mode,
+    buffer: std::vec::IntoIter<Value<'static>>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Mode {
+    /// Top-level arrays are unwrapped into one record per item
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "stream")]
+    Stream,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+/// Converts a MessagePack value, integers above `i64::MAX` become strings so
+/// no digit is lost, and extensions `{"type": <ext type>, "data": <bytes>}`.
+fn from_msgpack(value: rmpv::Value) -> Value<'static> {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(i) => match i.as_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::String(Cow::Owned(i.to_string())),
+        },
+        rmpv::Value::F32(f) => Value::Float(f as f64),
+        rmpv::Value::F64(f) => Value::Float(f),
+        rmpv::Value::String(s) if s.is_str() => Value::String(Cow::Owned(s.into_str().unwrap())),
+        rmpv::Value::String(s) => Value::Binary(s.into_bytes()),
+        rmpv::Value::Binary(b) => Value::Binary(b),
+        rmpv::Value::Array(values) => Value::Array(values.into_iter().map(from_msgpack).collect()),
+        rmpv::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        rmpv::Value::String(s) if s.is_str() => s.into_str().unwrap(),
+                        other => other.to_string(),
+                    };
+                    (Cow::Owned(key), from_msgpack(value))
+                })
+                .collect::<Object>(),
+        ),
+        rmpv::Value::Ext(ext_type, data) => Value::Object(
+            [
+                (Cow::Borrowed("type"), Value::Int(ext_type.into())),
+                (Cow::Borrowed("data"), Value::Binary(data)),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    }
+}
+
+impl<R: AsRef<[u8]> + Send + Sync> ValueReader for Reader<R> {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        loop {
+            if let Some(value) = self.buffer.next() {
+                return Ok(Some(value));
+            }
+
+            let mut slice = &self.reader.as_ref()[self.offset..];
+
+            if slice.is_empty() {
+                return Ok(None);
+            }
+
+            let value = rmpv::decode::read_value(&mut slice).map_err(Error::input)?;
+            self.offset = self.reader.as_ref().len() - slice.len();
+
+            match (&self.mode, from_msgpack(value)) {
+                (Mode::Auto, Value::Array(values)) => self.buffer = values.into_iter(),
+                (_, value) => return Ok(Some(value)),
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.offset = 0;
+        self.buffer = vec![].into_iter();
+    }
+}
+
+impl<R: AsRef<[u8]> + Send + Sync> Reader<R> {
+    pub fn new(inner: R, opts: Options) -> Result<Self> {
+        Ok(Self {
+            offset: 0,
+            reader: inner,
+            mode: opts.mode,
+            buffer: vec![].into_iter(),
+        })
+    }
+}
+
+#[test]
+fn msgpack_read() {
+    use rmpv::Value as Mp;
+
+    let mut input = vec![];
+    let records = Mp::Array(vec![
+        Mp::Map(vec![
+            (Mp::from("id"), Mp::from(u64::MAX)),
+            (Mp::from("ext"), Mp::Ext(5, vec![1, 2])),
+        ]),
+        Mp::Map(vec![(Mp::from(1), Mp::from(-1.5))]),
+    ]);
+    rmpv::encode::write_value(&mut input, &records).unwrap();
+
+    let mut reader = Reader::new(input.clone(), Default::default()).unwrap();
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        Value::Object(
+            [
+                (
+                    Cow::Borrowed("id"),
+                    Value::String("18446744073709551615".into())
+                ),
+                (
+                    Cow::Borrowed("ext"),
+                    Value::Object(
+                        [
+                            (Cow::Borrowed("type"), Value::Int(5)),
+                            (Cow::Borrowed("data"), Value::Binary(vec![1, 2])),
+                        ]
+                        .into_iter()
+                        .collect()
+                    )
+                ),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+    assert_eq!(reader.next().unwrap().unwrap(), crate::value!({"1": -1.5}));
+    assert!(reader.next().unwrap().is_none());
+
+    let opts = Options { mode: Mode::Stream };
+    let mut reader = Reader::new(input, opts).unwrap();
+    assert!(matches!(reader.next().unwrap(), Some(Value::Array(_))));
+    assert!(reader.next().unwrap().is_none());
+}
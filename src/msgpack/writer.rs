@@ -0,0 +1,72 @@
+use crate::reader::ValueReader;
+use crate::{Error, Result};
+
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Mode {
+    #[default]
+    #[serde(rename = "stream")]
+    Stream,
+    #[serde(rename = "array")]
+    Array,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+pub fn write<W: Write, R: ValueReader>(mut inner: W, mut reader: R, opts: Options) -> Result<()> {
+    if let Mode::Array = opts.mode {
+        let mut count = 0;
+        while reader.next()?.is_some() {
+            count += 1;
+        }
+        reader.reset();
+
+        rmp::encode::write_array_len(&mut inner, count).map_err(Error::output)?;
+    }
+
+    while let Some(row) = reader.next()? {
+        let mut s = rmp_serde::Serializer::new(&mut inner);
+        row.serialize(&mut s).map_err(Error::output)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn msgpack_write() {
+    use crate::msgpack::reader::{Options as ReaderOptions, Reader};
+    use crate::Value;
+
+    let input = r#"{"id":1,"name":"a","tags":["x"],"score":null}
+{"id":-2,"name":"b","tags":[],"score":1.5}"#;
+    let write_with = |opts: Options| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, opts).unwrap();
+        out
+    };
+
+    for mode in [Mode::Stream, Mode::Array] {
+        let is_array = matches!(mode, Mode::Array);
+        let out = write_with(Options { mode });
+        assert_eq!(out[0] == 0x92, is_array);
+
+        let mut reader = Reader::new(out, ReaderOptions::default()).unwrap();
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"id": 1, "name": "a", "tags": ["x"], "score": null})
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"id": -2, "name": "b", "tags": [], "score": 1.5})
+        );
+        assert!(reader.next().unwrap().is_none());
+    }
+}
@@ -0,0 +1,374 @@
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
+
+use crate::reader::{coerce_data_types, infer_value, Object, Value, ValueReader};
+use crate::{DataType, Error, Field, Result};
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Name of the generated surrogate key, the foreign keys are named after
+    /// the parent table followed by `_id`
+    pub id_column: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            id_column: "_id".into(),
+        }
+    }
+}
+
+/// A table of the normalised output, its rows are the objects found by
+/// following `path` from each record.
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub name: String,
+    /// Keys of the nested arrays leading from a record to the rows, each
+    /// preceded by the keys of the objects the array sits in
+    pub path: Vec<Vec<String>>,
+    /// Index of the parent table
+    pub parent: Option<usize>,
+    /// Keys of the arrays moved to child tables, as in `path`
+    pub children: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Name of the column referencing the parent row.
+    pub fn parent_column(&self, tables: &[Table]) -> Option<String> {
+        self.parent.map(|p| format!("{}_id", tables[p].name))
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Infers the type of a record leaving out the nulls and empty arrays, they
+/// say nothing about the type and would turn the arrays of objects found in
+/// other records into strings once coerced.
+fn infer(value: &Value) -> DataType {
+    match value {
+        Value::Object(object) => DataType::Object(
+            object
+                .iter()
+                .filter(|(_, value)| !is_empty(value))
+                .map(|(key, value)| Field::new(key.to_string(), infer(value), false))
+                .collect(),
+        ),
+        Value::Array(items) => DataType::Array(Box::new(coerce_data_types(
+            items
+                .iter()
+                .filter(|item| !matches!(item, Value::Null))
+                .map(infer)
+                .collect(),
+        ))),
+        value => infer_value(value),
+    }
+}
+
+/// Infers the schema the tables are listed from, see [`tables`].
+pub fn infer_schema<R: ValueReader>(mut reader: R) -> Result<DataType> {
+    let mut types = vec![];
+
+    while let Some(value) = reader.next()? {
+        let dt = infer(&value);
+        if !types.contains(&dt) {
+            types.push(dt);
+        }
+    }
+
+    reader.reset();
+
+    Ok(coerce_data_types(types))
+}
+
+/// Collects the arrays of objects of `dt`, looking into the nested objects.
+fn object_array_fields<'a>(
+    dt: &'a DataType,
+    prefix: &[String],
+    arrays: &mut Vec<(Vec<String>, &'a DataType)>,
+) {
+    if let DataType::Object(fields) = dt {
+        for field in fields {
+            let mut keys = prefix.to_vec();
+            keys.push(field.name.clone());
+
+            match &field.data_type {
+                DataType::Array(item) if matches!(**item, DataType::Object(_)) => {
+                    arrays.push((keys, item.as_ref()))
+                }
+                DataType::Object(_) => object_array_fields(&field.data_type, &keys, arrays),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Lists the tables of the normalised schema, the root table named `name`
+/// comes first and each child table after its parent. The child tables are
+/// named after their parent and the keys leading to the array, two of them
+/// ending up with the same name is an error.
+pub fn tables(dt: &DataType, name: &str) -> Result<Vec<Table>> {
+    let mut tables = vec![];
+    let mut names = HashSet::new();
+    let mut queue = VecDeque::from([(name.to_string(), vec![], None, dt)]);
+
+    while let Some((name, path, parent, dt)) = queue.pop_front() {
+        if !names.insert(name.clone()) {
+            return Err(Error::InputError(format!(
+                "Several tables would be named `{}`, rename the fields leading to them",
+                name
+            )));
+        }
+
+        let mut children = vec![];
+        object_array_fields(dt, &[], &mut children);
+        let index = tables.len();
+
+        tables.push(Table {
+            name: name.clone(),
+            path: path.clone(),
+            parent,
+            children: children.iter().map(|(keys, _)| keys.clone()).collect(),
+        });
+
+        for (keys, item) in children {
+            let child_name = format!("{}_{}", name, keys.join("_"));
+            let mut child_path = path.clone();
+            child_path.push(keys);
+            queue.push_back((child_name, child_path, Some(index), item));
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Removes the value found by following `keys` through the nested objects.
+fn take<'a>(object: &mut Object<'a>, keys: &[String]) -> Option<Value<'a>> {
+    let (last, parents) = keys.split_last()?;
+    let mut object = object;
+
+    for key in parents {
+        match object.get_mut(key.as_str()) {
+            Some(Value::Object(inner)) => object = inner,
+            _ => return None,
+        }
+    }
+
+    object.remove(last.as_str())
+}
+
+/// Reads the rows of one table of the normalised schema from the records of
+/// `reader`, numbering the objects of each nesting level in reading order so
+/// the keys match between the tables.
+pub struct TableReader<R> {
+    reader: R,
+    table: Table,
+    id_column: String,
+    parent_column: Option<String>,
+    /// Last id given at each nesting level
+    ids: Vec<i64>,
+    buffer: VecDeque<Value<'static>>,
+}
+
+impl<R: ValueReader> TableReader<R> {
+    pub fn new(mut reader: R, tables: &[Table], index: usize, opts: &Options) -> Self {
+        let table = tables[index].clone();
+        reader.reset();
+
+        Self {
+            reader,
+            ids: vec![0; table.path.len() + 1],
+            parent_column: table.parent_column(tables),
+            table,
+            id_column: opts.id_column.clone(),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    fn push_rows(&mut self, record: Object<'static>) -> Result<()> {
+        self.ids[0] += 1;
+        // (id, parent id, object) of the current nesting level
+        let mut level = vec![(self.ids[0], 0, record)];
+
+        for (depth, keys) in self.table.path.iter().enumerate() {
+            let mut next = vec![];
+
+            for (id, _, mut object) in level {
+                let Some(Value::Array(items)) = take(&mut object, keys) else {
+                    continue;
+                };
+
+                for item in items {
+                    if let Value::Object(item) = item {
+                        self.ids[depth + 1] += 1;
+                        next.push((self.ids[depth + 1], id, item));
+                    }
+                }
+            }
+
+            level = next;
+        }
+
+        for (id, parent_id, mut object) in level {
+            let generated = std::iter::once(&self.id_column).chain(&self.parent_column);
+            for column in generated {
+                if object.contains_key(column.as_str()) {
+                    return Err(Error::InputError(format!(
+                        "Field `{}` of table `{}` clashes with a generated key, rename it or change `id_column`",
+                        column, self.table.name
+                    )));
+                }
+            }
+
+            let mut row = Object::with_capacity(object.len() + 2);
+            row.insert(Cow::Owned(self.id_column.clone()), Value::Int(id));
+            if let Some(column) = &self.parent_column {
+                row.insert(Cow::Owned(column.clone()), Value::Int(parent_id));
+            }
+
+            for keys in &self.table.children {
+                take(&mut object, keys);
+            }
+            row.extend(object);
+
+            self.buffer.push_back(Value::Object(row));
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: ValueReader> ValueReader for TableReader<R> {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        while self.buffer.is_empty() {
+            match self.reader.next()? {
+                Some(Value::Object(record)) => {
+                    let Value::Object(record) = Value::Object(record).into_owned() else {
+                        unreachable!()
+                    };
+                    self.push_rows(record)?;
+                }
+                Some(_) => return Err("Expected an object".into()),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(self.buffer.pop_front())
+    }
+
+    fn reset(&mut self) {
+        self.reader.reset();
+        self.ids.iter_mut().for_each(|id| *id = 0);
+        self.buffer.clear();
+    }
+}
+
+#[test]
+fn normalize_tables() {
+    let input = r#"{"id":"a","items":[{"sku":1,"parts":[{"n":1},{"n":2}]},{"sku":2}]}
+{"id":"b","items":[]}
+{"id":"c","items":[{"sku":3,"parts":[{"n":3}]}]}"#;
+
+    let mut reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let tables = tables(&infer_schema(&mut reader).unwrap(), "orders").unwrap();
+
+    assert_eq!(
+        tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["orders", "orders_items", "orders_items_parts"]
+    );
+
+    let rows = |index: usize, reader: &mut crate::json::reader::RecordReader<&[u8]>| {
+        let mut table = TableReader::new(reader, &tables, index, &Default::default());
+        let mut rows = vec![];
+        while let Some(row) = table.next().unwrap() {
+            rows.push(serde_json::to_string(&row).unwrap());
+        }
+        rows
+    };
+
+    assert_eq!(
+        rows(0, &mut reader),
+        vec![
+            r#"{"_id":1,"id":"a"}"#,
+            r#"{"_id":2,"id":"b"}"#,
+            r#"{"_id":3,"id":"c"}"#
+        ]
+    );
+    assert_eq!(
+        rows(1, &mut reader),
+        vec![
+            r#"{"_id":1,"orders_id":1,"sku":1}"#,
+            r#"{"_id":2,"orders_id":1,"sku":2}"#,
+            r#"{"_id":3,"orders_id":3,"sku":3}"#
+        ]
+    );
+    assert_eq!(
+        rows(2, &mut reader),
+        vec![
+            r#"{"_id":1,"orders_items_id":1,"n":1}"#,
+            r#"{"_id":2,"orders_items_id":1,"n":2}"#,
+            r#"{"_id":3,"orders_items_id":3,"n":3}"#
+        ]
+    );
+}
+
+#[test]
+fn normalize_key_clash() {
+    let input = r#"{"_id":"a","items":[{"sku":1}]}"#;
+
+    let mut reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let tables = tables(&infer_schema(&mut reader).unwrap(), "orders").unwrap();
+
+    let mut table = TableReader::new(&mut reader, &tables, 0, &Default::default());
+    assert!(matches!(table.next(), Err(Error::InputError(_))));
+
+    let opts = Options {
+        id_column: "row_id".into(),
+    };
+    let mut table = TableReader::new(&mut reader, &tables, 1, &opts);
+    assert_eq!(
+        serde_json::to_string(&table.next().unwrap()).unwrap(),
+        r#"{"row_id":1,"orders_id":1,"sku":1}"#
+    );
+}
+
+#[test]
+fn normalize_nested_objects() {
+    let input = r#"{"id":"a","meta":{"n":1,"tags":[{"t":"x"},{"t":"y"}]}}"#;
+
+    let mut reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let tables = tables(&infer_schema(&mut reader).unwrap(), "orders").unwrap();
+
+    assert_eq!(
+        tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+        vec!["orders", "orders_meta_tags"]
+    );
+
+    let mut table = TableReader::new(&mut reader, &tables, 0, &Default::default());
+    assert_eq!(
+        serde_json::to_string(&table.next().unwrap()).unwrap(),
+        r#"{"_id":1,"id":"a","meta":{"n":1}}"#
+    );
+
+    let mut table = TableReader::new(&mut reader, &tables, 1, &Default::default());
+    assert_eq!(
+        serde_json::to_string(&table.next().unwrap()).unwrap(),
+        r#"{"_id":1,"orders_id":1,"t":"x"}"#
+    );
+
+    let input = r#"{"meta":{"tags":[{"t":"x"}]},"meta_tags":[{"t":"y"}]}"#;
+    let mut reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let error = self::tables(&infer_schema(&mut reader).unwrap(), "orders").unwrap_err();
+    assert!(error.to_string().contains("`orders_meta_tags`"));
+}
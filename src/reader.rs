@@ -1,6 +1,7 @@
 use std::{borrow::Cow, hash::Hash};
 
-use crate::{error::Result, flatten::flatten, Error, ReaderOptions};
+use crate::error::{Limit, Result};
+use crate::{erlang::Limits, flatten::flatten, Error, ReaderOptions};
 use indexmap::map::IndexMap;
 use indexmap::set::IndexSet;
 use serde::{Deserialize, Serialize};
@@ -25,7 +26,20 @@ pub enum Value<'a> {
     Float(f64),
     Array(Vec<Value<'a>>),
     Object(Object<'a>),
-    Binary(Vec<u8>),
+    Binary(#[serde(serialize_with = "serialize_binary")] Vec<u8>),
+}
+
+/// Bytes in the binary formats, hexadecimal strings in the text ones like the
+/// writers that format values themselves.
+fn serialize_binary<S: serde::Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
 }
 
 impl<'a> Default for Value<'a> {
@@ -74,6 +88,26 @@ impl<'a> Value<'a> {
         }
     }
 
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(v) => Value::Bool(v),
+            Value::String(v) => Value::String(Cow::Owned(v.into_owned())),
+            Value::Int(v) => Value::Int(v),
+            Value::Float(v) => Value::Float(v),
+            Value::Array(values) => {
+                Value::Array(values.into_iter().map(Value::into_owned).collect())
+            }
+            Value::Object(object) => Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (Cow::Owned(key.into_owned()), value.into_owned()))
+                    .collect(),
+            ),
+            Value::Binary(v) => Value::Binary(v),
+        }
+    }
+
     pub fn flatten_object(self) -> Self {
         match self {
             Value::Object(map) => Value::Object(flatten(map)),
@@ -178,6 +212,7 @@ pub fn coerce_value<'a>(
         (Value::String(_), DataType::String) => Ok(()),
         (Value::Int(_), DataType::Int) => Ok(()),
         (Value::Float(_), DataType::Float) => Ok(()),
+        (Value::Binary(_), DataType::Binary) => Ok(()),
         (Value::Bool(true), DataType::Int) => {
             *value = Value::Int(1);
             Ok(())
@@ -267,6 +302,7 @@ pub fn infer_value(value: &Value) -> DataType {
         Value::Float(_) => DataType::Float,
         Value::Bool(_) => DataType::Bool,
         Value::String(_str) => DataType::String,
+        Value::Binary(_) => DataType::Binary,
         Value::Object(object) => DataType::Object(
             object
                 .iter()
@@ -284,7 +320,6 @@ pub fn infer_value(value: &Value) -> DataType {
                 types.into_iter().collect::<Vec<_>>(),
             )))
         }
-        v => todo!("{:?}", v),
     }
 }
 
@@ -312,7 +347,7 @@ pub fn coerce_data_type(a: &mut DataType, b: &mut DataType) -> DataType {
             let mut fields = Vec::with_capacity(std::cmp::max(lhs.len(), rhs.len()));
 
             for mut a in lhs.drain(..) {
-                let Some(index)= rhs.iter_mut().position(|f| f.name == a.name) else {
+                let Some(index) = rhs.iter_mut().position(|f| f.name == a.name) else {
                     a.is_nullable = true;
                     fields.push(a);
                     continue;
@@ -386,6 +421,15 @@ impl<T: ValueReader + ?Sized> ValueReader for Box<T> {
     }
 }
 
+impl<T: ValueReader + ?Sized> ValueReader for &mut T {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        (**self).next()
+    }
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
+
 pub struct RowIterator<R>(R, Vec<Field>);
 
 impl<R> RowIterator<R>
@@ -422,6 +466,87 @@ where
     }
 }
 
+/// Errors once more than `max_records` records are read.
+pub struct LimitedReader<R> {
+    reader: R,
+    max_records: usize,
+    count: usize,
+}
+
+impl<R: ValueReader> LimitedReader<R> {
+    pub fn new(reader: R, max_records: usize) -> Self {
+        Self {
+            reader,
+            max_records,
+            count: 0,
+        }
+    }
+}
+
+impl<R: ValueReader> ValueReader for LimitedReader<R> {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let value = self.reader.next()?;
+
+        if value.is_some() {
+            self.count += 1;
+            if self.count > self.max_records {
+                return Err(Error::limit(Limit::Records, self.max_records as u64));
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn reset(&mut self) {
+        self.reader.reset();
+        self.count = 0;
+    }
+}
+
+/// Opens `path` like [`new_reader`], erroring if the input goes over `limits`.
+pub fn new_limited_reader(
+    path: &str,
+    options: ReaderOptions,
+    limits: Limits,
+) -> Result<Box<dyn ValueReader>> {
+    if let Some(max_bytes) = limits.max_bytes {
+        if std::fs::metadata(path)?.len() > max_bytes {
+            return Err(Error::limit(Limit::Bytes, max_bytes));
+        }
+    }
+
+    let mut options = options;
+
+    if let Some(max_line_length) = limits.max_line_length {
+        match &mut options {
+            ReaderOptions::Csv(opts) => opts.max_line_length = Some(max_line_length),
+            _ => {
+                return Err(Error::CustomError(
+                    "`max_line_length` only applies to CSV".into(),
+                ))
+            }
+        }
+    }
+
+    if let Some(max_record_length) = limits.max_record_length {
+        match &mut options {
+            ReaderOptions::Json(opts) => opts.max_record_length = Some(max_record_length),
+            _ => {
+                return Err(Error::CustomError(
+                    "`max_record_length` only applies to JSON".into(),
+                ))
+            }
+        }
+    }
+
+    let reader = new_reader(path, options)?;
+
+    Ok(match limits.max_records {
+        Some(max_records) => Box::new(LimitedReader::new(reader, max_records)),
+        None => reader,
+    })
+}
+
 pub fn new_reader(path: &str, options: ReaderOptions) -> Result<Box<dyn ValueReader>> {
     let input_file = std::fs::File::open(path)?;
     let data = unsafe { memmap2::Mmap::map(&input_file)? };
@@ -441,5 +566,58 @@ pub fn new_reader(path: &str, options: ReaderOptions) -> Result<Box<dyn ValueRea
             std::io::Cursor::new(data),
             opts,
         )?) as Box<dyn ValueReader>,
+        ReaderOptions::Html(opts) => {
+            Box::new(crate::html::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::Markdown(opts) => {
+            Box::new(crate::markdown::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::Yaml(opts) => {
+            Box::new(crate::yaml::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::Ini(opts) => {
+            Box::new(crate::ini::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::MsgPack(opts) => {
+            Box::new(crate::msgpack::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::Cbor(opts) => {
+            Box::new(crate::cbor::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::Bson(opts) => {
+            Box::new(crate::bson::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::FixedWidth(opts) => {
+            Box::new(crate::fixed_width::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::Sql(opts) => {
+            Box::new(crate::sql::reader::Reader::new(data, opts)?) as Box<dyn ValueReader>
+        }
+        ReaderOptions::SqliteDb(opts) => {
+            Box::new(crate::sqlite::reader::Reader::new(path, opts)?) as Box<dyn ValueReader>
+        }
     })
 }
+
+#[test]
+fn limited_reader_formats() {
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+    std::fs::write(&path, "{\"a\":1}\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    let json = || ReaderOptions::Json(Default::default());
+    let limits = |max_line_length, max_record_length| Limits {
+        max_line_length,
+        max_record_length,
+        ..Default::default()
+    };
+
+    assert!(new_limited_reader(path, json(), limits(None, Some(100))).is_ok());
+    assert!(new_limited_reader(path, json(), limits(Some(100), None)).is_err());
+    assert!(new_limited_reader(
+        path,
+        ReaderOptions::Csv(Default::default()),
+        limits(None, Some(100))
+    )
+    .is_err());
+}
@@ -0,0 +1,1363 @@
+use crate::{
+    error::{Error, Result},
+    reader::{self, Object, Value},
+    sql::writer::Syntax,
+    DataType, Field,
+};
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Dialect of the dump, guessed from its content when missing
+    pub syntax: Option<Syntax>,
+    /// Only read the rows of this table, qualified by its schema when the
+    /// dump has one, like `public.users`
+    pub table: Option<String>,
+    /// Adds a column with the name of the table each row comes from
+    pub table_column: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Table {
+    pub name: String,
+    /// Columns declared by `CREATE TABLE`, or named after the `INSERT`
+    /// column lists. Columns with an unknown type are `DataType::Null`.
+    pub fields: Vec<Field>,
+}
+
+/// Reads the rows of the `INSERT` statements and `COPY ... FROM stdin` blocks
+/// of a SQL dump, typed after the matching `CREATE TABLE` when there is one.
+pub struct Reader {
+    text: String,
+    syntax: Syntax,
+    /// Position of the next statement
+    pos: usize,
+    parser: Parser,
+    table_column: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Str(String),
+    Number(String),
+    Blob(Vec<u8>),
+    Punct(char),
+    /// `||`
+    Concat,
+}
+
+impl Token {
+    fn is_word(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn identifier(self) -> Option<String> {
+        match self {
+            Token::Word(w) | Token::Quoted(w) => Some(w),
+            _ => None,
+        }
+    }
+}
+
+enum Literal {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    /// A field of a `COPY` block, typed like CSV when the column has no type
+    Text(String),
+    Blob(Vec<u8>),
+    /// Source of an expression that isn't a plain literal, such as `now()`
+    Raw(String),
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    pos: usize,
+    syntax: Syntax,
+    peeked: Option<(Token, usize, usize)>,
+    /// Bounds of the last token returned by `next`
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str, syntax: Syntax) -> Self {
+        Self {
+            text,
+            pos: 0,
+            syntax,
+            peeked: None,
+            start: 0,
+            end: 0,
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T> {
+        let line = self.text[..self.pos.min(self.text.len())]
+            .matches('\n')
+            .count()
+            + 1;
+        Err(Error::InputError(format!("{} on line {}", message, line)))
+    }
+
+    fn next(&mut self) -> Result<Option<Token>> {
+        if let Some((token, start, end)) = self.peeked.take() {
+            self.start = start;
+            self.end = end;
+            return Ok(Some(token));
+        }
+
+        self.skip_whitespace()?;
+        let start = self.pos;
+        let token = self.read_token()?;
+        self.start = start;
+        self.end = self.pos;
+
+        Ok(token)
+    }
+
+    fn peek(&mut self) -> Result<Option<&Token>> {
+        if self.peeked.is_none() {
+            let (start, end) = (self.start, self.end);
+            if let Some(token) = self.next()? {
+                self.peeked = Some((token, self.start, self.end));
+            }
+            self.start = start;
+            self.end = end;
+        }
+
+        Ok(self.peeked.as_ref().map(|(t, _, _)| t))
+    }
+
+    fn next_is(&mut self, expected: &Token) -> Result<bool> {
+        if self.peek()? == Some(expected) {
+            self.next()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn next_is_word(&mut self, keyword: &str) -> Result<bool> {
+        if matches!(self.peek()?, Some(t) if t.is_word(keyword)) {
+            self.next()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("--")
+                || (self.syntax == Syntax::Mysql && trimmed.starts_with('#'))
+            {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                match trimmed.find("*/") {
+                    Some(end) => self.pos += end + 2,
+                    None => return self.error("Unterminated comment"),
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> Result<Option<Token>> {
+        let rest = self.rest();
+        let mut chars = rest.chars();
+        let Some(c) = chars.next() else {
+            return Ok(None);
+        };
+        let next = chars.next();
+
+        let token = match (c, next) {
+            ('\'', _) => {
+                self.pos += 1;
+                self.read_str('\'', self.syntax.backslash_escapes())?
+            }
+            ('E' | 'e', Some('\'')) => {
+                self.pos += 2;
+                self.read_str('\'', true)?
+            }
+            ('N' | 'n', Some('\'')) => {
+                self.pos += 2;
+                self.read_str('\'', self.syntax.backslash_escapes())?
+            }
+            ('X' | 'x', Some('\'')) => {
+                self.pos += 2;
+                let hex = self.read_string('\'', false)?;
+                match hex::decode(hex) {
+                    Ok(bytes) => Token::Blob(bytes),
+                    Err(_) => return self.error("Invalid hexadecimal literal"),
+                }
+            }
+            ('0', Some('x' | 'X')) if rest[2..].starts_with(|c: char| c.is_ascii_hexdigit()) => {
+                let len = rest[2..]
+                    .find(|c: char| !c.is_ascii_hexdigit())
+                    .unwrap_or(rest.len() - 2);
+                let hex = &rest[2..2 + len];
+                self.pos += 2 + len;
+                match hex::decode(hex) {
+                    Ok(bytes) => Token::Blob(bytes),
+                    Err(_) => return self.error("Invalid hexadecimal literal"),
+                }
+            }
+            ('"', _) if matches!(self.syntax, Syntax::Mysql | Syntax::BigQuery) => {
+                self.pos += 1;
+                self.read_str('"', true)?
+            }
+            ('"', _) => {
+                self.pos += 1;
+                Token::Quoted(self.read_string('"', false)?)
+            }
+            ('`', _) => {
+                self.pos += 1;
+                Token::Quoted(self.read_string('`', false)?)
+            }
+            ('[', _) if matches!(self.syntax, Syntax::Sqlite | Syntax::SqlServer) => {
+                self.pos += 1;
+                match self.rest().find(']') {
+                    Some(end) => {
+                        let id = self.rest()[..end].to_string();
+                        self.pos += end + 1;
+                        Token::Quoted(id)
+                    }
+                    None => return self.error("Unterminated identifier"),
+                }
+            }
+            ('$', _) => match dollar_tag(rest) {
+                Some(tag) => {
+                    self.pos += tag.len();
+                    match self.rest().find(tag) {
+                        Some(end) => {
+                            let s = self.rest()[..end].to_string();
+                            self.pos += end + tag.len();
+                            Token::Str(s)
+                        }
+                        None => return self.error("Unterminated dollar-quoted string"),
+                    }
+                }
+                None => {
+                    self.pos += 1;
+                    Token::Punct('$')
+                }
+            },
+            ('|', Some('|')) => {
+                self.pos += 2;
+                Token::Concat
+            }
+            (c, _) if c.is_ascii_digit() || (c == '.' && matches!(next, Some('0'..='9'))) => {
+                let mut len = 0;
+                let bytes = rest.as_bytes();
+                while len < bytes.len() {
+                    match bytes[len] {
+                        b'0'..=b'9' | b'.' => len += 1,
+                        b'e' | b'E' => {
+                            len += 1;
+                            if matches!(bytes.get(len), Some(b'+' | b'-')) {
+                                len += 1;
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                self.pos += len;
+                Token::Number(rest[..len].to_string())
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                    .unwrap_or(rest.len());
+                self.pos += len;
+                Token::Word(rest[..len].to_string())
+            }
+            (c, _) => {
+                self.pos += c.len_utf8();
+                Token::Punct(c)
+            }
+        };
+
+        Ok(Some(token))
+    }
+
+    /// Reads a string literal, binary when its escaped bytes aren't UTF-8.
+    fn read_str(&mut self, quote: char, backslash: bool) -> Result<Token> {
+        let bytes = self.read_bytes(quote, backslash)?;
+
+        Ok(match String::from_utf8(bytes) {
+            Ok(s) => Token::Str(s),
+            Err(e) => Token::Blob(e.into_bytes()),
+        })
+    }
+
+    fn read_string(&mut self, quote: char, backslash: bool) -> Result<String> {
+        let bytes = self.read_bytes(quote, backslash)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Reads up to the closing `quote`, which is escaped by doubling it and,
+    /// when `backslash` is set, by a backslash.
+    fn read_bytes(&mut self, quote: char, backslash: bool) -> Result<Vec<u8>> {
+        let mut s = vec![];
+        let mut chars = self.rest().char_indices();
+        let push = |s: &mut Vec<u8>, c: char| {
+            s.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        };
+
+        while let Some((i, c)) = chars.next() {
+            if c == quote {
+                if self.rest()[i + 1..].starts_with(quote) {
+                    chars.next();
+                    push(&mut s, quote);
+                    continue;
+                }
+                self.pos += i + 1;
+                return Ok(s);
+            }
+
+            if c == '\\' && backslash {
+                match chars.next() {
+                    Some((_, 'n')) => s.push(b'\n'),
+                    Some((_, 'r')) => s.push(b'\r'),
+                    Some((_, 't')) => s.push(b'\t'),
+                    Some((_, 'b')) => s.push(0x08),
+                    Some((_, 'f')) => s.push(0x0c),
+                    Some((_, '0')) => s.push(0),
+                    Some((_, 'Z')) => s.push(0x1a),
+                    Some((i, 'x')) => {
+                        let hex = self.rest().get(i + 1..i + 3).unwrap_or("");
+                        match u8::from_str_radix(hex, 16) {
+                            Ok(b) if hex.len() == 2 => {
+                                s.push(b);
+                                chars.next();
+                                chars.next();
+                            }
+                            _ => s.push(b'x'),
+                        }
+                    }
+                    // MySQL keeps the backslash of the LIKE wildcards
+                    Some((_, c @ ('%' | '_'))) => {
+                        s.push(b'\\');
+                        push(&mut s, c);
+                    }
+                    Some((_, c)) => push(&mut s, c),
+                    None => break,
+                }
+                continue;
+            }
+
+            push(&mut s, c);
+        }
+
+        self.error("Unterminated string")
+    }
+
+    /// Skips to the end of the current statement.
+    fn skip_statement(&mut self) -> Result<()> {
+        while let Some(token) = self.next()? {
+            if token == Token::Punct(';') {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a possibly schema-qualified name, its parts joined by `.`.
+    fn qualified_name(&mut self) -> Result<Option<String>> {
+        let Some(mut name) = self.next()?.and_then(Token::identifier) else {
+            return Ok(None);
+        };
+
+        while self.next_is(&Token::Punct('.'))? {
+            match self.next()?.and_then(Token::identifier) {
+                Some(part) => {
+                    name.push('.');
+                    name.push_str(&part);
+                }
+                None => return self.error("Expected a name after `.`"),
+            }
+        }
+
+        Ok(Some(name))
+    }
+
+    /// Reads a parenthesized list of column names, if there is one.
+    fn column_list(&mut self) -> Result<Option<Vec<String>>> {
+        if !self.next_is(&Token::Punct('('))? {
+            return Ok(None);
+        }
+
+        let mut columns = vec![];
+
+        loop {
+            match self.next()? {
+                Some(Token::Punct(')')) => break,
+                Some(Token::Punct(',')) => {}
+                Some(token) => match token.identifier() {
+                    Some(column) => columns.push(column),
+                    None => return self.error("Expected a column name"),
+                },
+                None => return self.error("Unterminated column list"),
+            }
+        }
+
+        Ok(Some(columns))
+    }
+
+    /// Reads one value of an `INSERT` row.
+    fn literal(&mut self) -> Result<Literal> {
+        let mut literal = self.term()?;
+
+        while self.next_is(&Token::Concat)? {
+            literal = match (literal, self.term()?) {
+                (Literal::Str(mut lhs), Literal::Str(rhs)) => {
+                    lhs.push_str(&rhs);
+                    Literal::Str(lhs)
+                }
+                _ => return self.error("Only strings can be concatenated"),
+            };
+        }
+
+        Ok(literal)
+    }
+
+    fn term(&mut self) -> Result<Literal> {
+        let literal = match self.next()? {
+            Some(Token::Number(n)) => Literal::Number(n),
+            Some(Token::Punct(sign @ ('-' | '+')))
+                if matches!(self.peek()?, Some(Token::Number(_))) =>
+            {
+                let Some(Token::Number(n)) = self.next()? else {
+                    unreachable!()
+                };
+                Literal::Number(if sign == '-' { format!("-{}", n) } else { n })
+            }
+            Some(Token::Str(s)) => Literal::Str(s),
+            Some(Token::Blob(b)) => Literal::Blob(b),
+            Some(t) if t.is_word("NULL") => Literal::Null,
+            Some(t) if t.is_word("TRUE") => Literal::Bool(true),
+            Some(t) if t.is_word("FALSE") => Literal::Bool(false),
+            // MySQL charset introducers such as `_utf8mb4'...'` and `_binary'...'`
+            Some(Token::Word(w))
+                if w.starts_with('_')
+                    && matches!(self.peek()?, Some(Token::Str(_) | Token::Blob(_))) =>
+            {
+                match (w.eq_ignore_ascii_case("_binary"), self.next()?) {
+                    (true, Some(Token::Str(s))) => Literal::Blob(s.into_bytes()),
+                    (false, Some(Token::Str(s))) => Literal::Str(s),
+                    (_, Some(Token::Blob(b))) => Literal::Blob(b),
+                    _ => unreachable!(),
+                }
+            }
+            Some(t)
+                if (t.is_word("char") || t.is_word("replace"))
+                    && self.peek()? == Some(&Token::Punct('(')) =>
+            {
+                self.function(&t)?
+            }
+            Some(Token::Punct(c @ (',' | ')'))) => {
+                return self.error(&format!("Expected a value before `{}`", c))
+            }
+            Some(token) => {
+                let start = self.start;
+                self.skip_expression(if token == Token::Punct('(') { 1 } else { 0 })?;
+                return Ok(Literal::Raw(self.text[start..self.end].to_string()));
+            }
+            None => return self.error("Unexpected end of input"),
+        };
+
+        // Postgres casts such as `'{}'::jsonb`
+        if self.peek()? == Some(&Token::Punct(':')) {
+            self.skip_expression(0)?;
+        }
+
+        Ok(literal)
+    }
+
+    /// Evaluates the `char()` and `replace()` calls SQLite's `.dump` escapes
+    /// newlines and control characters with.
+    fn function(&mut self, name: &Token) -> Result<Literal> {
+        self.next()?;
+        let mut args = vec![];
+
+        loop {
+            args.push(self.literal()?);
+            match self.next()? {
+                Some(Token::Punct(',')) => {}
+                Some(Token::Punct(')')) => break,
+                _ => return self.error("Expected `,` or `)`"),
+            }
+        }
+
+        if name.is_word("char") {
+            let mut s = String::new();
+            for arg in args {
+                let c = match arg {
+                    Literal::Number(n) => n.parse().ok().and_then(char::from_u32),
+                    _ => None,
+                };
+                match c {
+                    Some(c) => s.push(c),
+                    None => return self.error("Invalid argument to char()"),
+                }
+            }
+            return Ok(Literal::Str(s));
+        }
+
+        match <[Literal; 3]>::try_from(args) {
+            Ok([Literal::Str(s), Literal::Str(from), Literal::Str(to)]) => {
+                Ok(Literal::Str(if from.is_empty() {
+                    s
+                } else {
+                    s.replace(&from, &to)
+                }))
+            }
+            _ => self.error("Invalid arguments to replace()"),
+        }
+    }
+
+    /// Skips tokens up to the next `,` or `)` outside of parentheses, `depth`
+    /// being the number of parentheses already opened.
+    fn skip_expression(&mut self, mut depth: usize) -> Result<()> {
+        loop {
+            match self.peek()? {
+                Some(Token::Punct(',' | ')')) if depth == 0 => return Ok(()),
+                Some(Token::Punct(';')) | None => return self.error("Unterminated row"),
+                Some(Token::Punct('(')) => depth += 1,
+                Some(Token::Punct(')')) => depth -= 1,
+                _ => {}
+            }
+            self.next()?;
+        }
+    }
+
+    /// Moves to the data of a `COPY ... FROM stdin` block, on the line after
+    /// the statement.
+    fn copy_start(&mut self) {
+        debug_assert!(self.peeked.is_none());
+
+        self.pos += self
+            .rest()
+            .find('\n')
+            .map(|i| i + 1)
+            .unwrap_or(self.rest().len());
+    }
+
+    /// Reads the next line of a `COPY` block, `None` at the `\.` ending it.
+    fn copy_line(&mut self) -> Result<Option<Vec<Option<String>>>> {
+        let rest = self.rest();
+        if rest.is_empty() {
+            return self.error("Unterminated COPY data");
+        }
+
+        let (line, len) = match rest.find('\n') {
+            Some(i) => (&rest[..i], i + 1),
+            None => (rest, rest.len()),
+        };
+        self.pos += len;
+
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line == "\\." {
+            return Ok(None);
+        }
+
+        Ok(Some(line.split('\t').map(copy_field).collect()))
+    }
+}
+
+/// Returns the `$tag$` opening a dollar-quoted string.
+fn dollar_tag(s: &str) -> Option<&str> {
+    let end = s[1..].find(|c: char| !(c.is_alphanumeric() || c == '_'))? + 1;
+    if s[end..].starts_with('$') && !s[1..end].starts_with(|c: char| c.is_ascii_digit()) {
+        Some(&s[..end + 1])
+    } else {
+        None
+    }
+}
+
+/// Unescapes a field of the `COPY` text format, `\N` being NULL.
+fn copy_field(field: &str) -> Option<String> {
+    if field == "\\N" {
+        return None;
+    }
+
+    if !field.contains('\\') {
+        return Some(field.to_string());
+    }
+
+    let mut s = String::with_capacity(field.len());
+    let mut chars = field.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            s.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => s.push('\n'),
+            Some('r') => s.push('\r'),
+            Some('t') => s.push('\t'),
+            Some('b') => s.push('\x08'),
+            Some('f') => s.push('\x0c'),
+            Some('v') => s.push('\x0b'),
+            Some(c @ '0'..='7') => {
+                let mut code = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(d) => {
+                            code = code * 8 + d;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                s.extend(char::from_u32(code));
+            }
+            Some(c) => s.push(c),
+            None => s.push('\\'),
+        }
+    }
+
+    Some(s)
+}
+
+/// Maps a declared column type back to the closest `DataType`.
+fn map_type(type_name: &str, args: &[String]) -> DataType {
+    let type_name = type_name.to_ascii_lowercase();
+    let first = type_name.split_whitespace().next().unwrap_or("");
+
+    match first {
+        "" => DataType::Null,
+        "tinyint" | "bit" if args.len() == 1 && args[0] == "1" => DataType::Bool,
+        "int" | "integer" | "bigint" | "smallint" | "tinyint" | "mediumint" | "int2" | "int4"
+        | "int8" | "serial" | "bigserial" | "smallserial" | "serial4" | "serial8" => DataType::Int,
+        "real" | "float" | "double" | "float4" | "float8" | "numeric" | "decimal" | "dec" => {
+            DataType::Float
+        }
+        "bool" | "boolean" => DataType::Bool,
+        "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" => {
+            DataType::Binary
+        }
+        "json" | "jsonb" => DataType::Object(vec![]),
+        _ => DataType::String,
+    }
+}
+
+fn is_constraint(token: &Token) -> bool {
+    [
+        "CONSTRAINT",
+        "PRIMARY",
+        "UNIQUE",
+        "KEY",
+        "INDEX",
+        "FOREIGN",
+        "CHECK",
+        "FULLTEXT",
+        "SPATIAL",
+        "EXCLUDE",
+    ]
+    .iter()
+    .any(|k| token.is_word(k))
+}
+
+fn is_column_option(token: &Token) -> bool {
+    [
+        "NOT",
+        "NULL",
+        "DEFAULT",
+        "PRIMARY",
+        "UNIQUE",
+        "REFERENCES",
+        "CHECK",
+        "CONSTRAINT",
+        "COLLATE",
+        "AUTO_INCREMENT",
+        "AUTOINCREMENT",
+        "GENERATED",
+        "COMMENT",
+        "SET",
+        "ON",
+    ]
+    .iter()
+    .any(|k| token.is_word(k))
+}
+
+/// Parses the tokens of a column definition into a field.
+fn column_definition(tokens: Vec<Token>) -> Option<Field> {
+    let mut tokens = tokens.into_iter().peekable();
+    let name = tokens.next()?.identifier()?;
+
+    let mut type_name = String::new();
+    let mut args = vec![];
+
+    while let Some(token) = tokens.next_if(|t| !is_column_option(t)) {
+        match token {
+            Token::Word(w) | Token::Quoted(w) => {
+                type_name.push_str(&w);
+                type_name.push(' ');
+            }
+            Token::Number(n) => args.push(n),
+            Token::Punct('[') => {
+                // Postgres arrays are dumped as text
+                type_name = "text".into();
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let rest = tokens.collect::<Vec<_>>();
+    let is_nullable = !rest
+        .windows(2)
+        .any(|w| (w[0].is_word("NOT") && w[1].is_word("NULL")) || w[0].is_word("PRIMARY"));
+
+    Some(Field::new(name, map_type(&type_name, &args), is_nullable))
+}
+
+fn to_value(literal: Literal, data_type: &DataType) -> Value<'static> {
+    let parse_number = |n: &str| -> Option<Value<'static>> {
+        if let Ok(i) = n.parse::<i64>() {
+            Some(Value::Int(i))
+        } else {
+            n.parse::<f64>().ok().map(Value::Float)
+        }
+    };
+
+    match (literal, data_type) {
+        (Literal::Null, _) => Value::Null,
+        (Literal::Bool(b), _) => Value::Bool(b),
+        (Literal::Blob(b), DataType::String) => {
+            Value::String(Cow::Owned(String::from_utf8_lossy(&b).into_owned()))
+        }
+        (Literal::Blob(b), _) => Value::Binary(b),
+        (Literal::Raw(s), _) => Value::String(Cow::Owned(s)),
+
+        (Literal::Number(n) | Literal::Str(n) | Literal::Text(n), DataType::Bool) => {
+            match n.to_ascii_lowercase().as_str() {
+                "1" | "t" | "true" | "y" | "yes" => Value::Bool(true),
+                "0" | "f" | "false" | "n" | "no" => Value::Bool(false),
+                _ => Value::String(Cow::Owned(n)),
+            }
+        }
+        (Literal::Number(n) | Literal::Str(n) | Literal::Text(n), DataType::Int) => {
+            parse_number(&n).unwrap_or(Value::String(Cow::Owned(n)))
+        }
+        (Literal::Number(n) | Literal::Str(n) | Literal::Text(n), DataType::Float) => n
+            .parse::<f64>()
+            .map(Value::Float)
+            .unwrap_or(Value::String(Cow::Owned(n))),
+        (Literal::Str(s) | Literal::Text(s), DataType::Binary) => {
+            // Postgres bytea hex output
+            match s.strip_prefix("\\x").and_then(|hex| hex::decode(hex).ok()) {
+                Some(bytes) => Value::Binary(bytes),
+                None => Value::Binary(s.into_bytes()),
+            }
+        }
+        (Literal::Str(s) | Literal::Text(s), DataType::Object(_) | DataType::Array(_)) => {
+            match serde_json::from_str::<Value>(&s) {
+                Ok(value) => value.into_owned(),
+                Err(_) => Value::String(Cow::Owned(s)),
+            }
+        }
+        (Literal::Number(n), DataType::Null) => {
+            parse_number(&n).unwrap_or(Value::String(Cow::Owned(n)))
+        }
+        (Literal::Text(s), DataType::Null) => reader::parse_bytes(s.as_bytes()).into_owned(),
+        (Literal::Number(s) | Literal::Str(s) | Literal::Text(s), _) => {
+            Value::String(Cow::Owned(s))
+        }
+    }
+}
+
+fn starts_with_keyword(line: &str, keyword: &str) -> bool {
+    matches!(line.get(..keyword.len()), Some(start) if start.eq_ignore_ascii_case(keyword))
+}
+
+/// Guesses the dialect of a dump from its header comments and the start of
+/// its statements, leaving out the values which could contain anything.
+fn detect_syntax(text: &str) -> Syntax {
+    for line in text.lines().map(str::trim_start) {
+        if line.starts_with("--") {
+            if line.contains("PostgreSQL") || line.contains("pg_dump") {
+                return Syntax::Postgres;
+            } else if line.contains("MySQL") || line.contains("MariaDB") {
+                return Syntax::Mysql;
+            }
+        } else if line.starts_with("/*!") || line.starts_with(") ENGINE=") {
+            return Syntax::Mysql;
+        } else if starts_with_keyword(line, "COPY ") && line.ends_with("FROM stdin;") {
+            return Syntax::Postgres;
+        } else if starts_with_keyword(line, "PRAGMA ") {
+            return Syntax::Sqlite;
+        } else if starts_with_keyword(line, "CREATE TABLE ") || line.starts_with('`') {
+            if line.contains('`') {
+                return Syntax::Mysql;
+            }
+        } else if starts_with_keyword(line, "INSERT ") {
+            let values = line
+                .to_ascii_uppercase()
+                .find("VALUES")
+                .unwrap_or(line.len());
+            if line[..values].contains('`') {
+                return Syntax::Mysql;
+            }
+        }
+    }
+
+    Syntax::Sqlite
+}
+
+/// What is known of the dump, read one statement at a time. The dump is read
+/// twice, first to list the tables with all their columns, including the ones
+/// declared after their rows, then for the rows.
+struct Parser {
+    only_table: Option<String>,
+    tables: Vec<Table>,
+    /// Whether the tables are being listed, the rows aren't kept then
+    scanning: bool,
+    /// Rows of the last statement not read yet
+    rows: VecDeque<(usize, Vec<Value<'static>>)>,
+    /// Table and column positions of the `COPY` block being read
+    copy: Option<(usize, Vec<usize>)>,
+}
+
+impl Parser {
+    fn table_index(&mut self, name: &str) -> usize {
+        match self.tables.iter().position(|t| t.name == name) {
+            Some(i) => i,
+            None => {
+                self.tables.push(Table {
+                    name: name.to_string(),
+                    fields: vec![],
+                });
+                self.tables.len() - 1
+            }
+        }
+    }
+
+    fn is_wanted(&self, name: &str) -> bool {
+        match &self.only_table {
+            Some(table) => table == name,
+            None => true,
+        }
+    }
+
+    /// Position of each listed column in the fields of `table`, adding the
+    /// columns it doesn't know about yet.
+    fn column_indexes(&mut self, table: usize, columns: Option<Vec<String>>) -> Vec<usize> {
+        let fields = &mut self.tables[table].fields;
+
+        match columns {
+            Some(columns) => columns
+                .into_iter()
+                .map(|c| match fields.iter().position(|f| f.name == c) {
+                    Some(i) => i,
+                    None => {
+                        fields.push(Field::new(c, DataType::Null, true));
+                        fields.len() - 1
+                    }
+                })
+                .collect(),
+            None => (0..fields.len()).collect(),
+        }
+    }
+
+    /// Stores a row whose values go to the fields at `indexes`, or in the
+    /// order of the fields when there is no column list.
+    fn push_row(&mut self, table: usize, indexes: &[usize], literals: Vec<Literal>) {
+        let fields = &mut self.tables[table].fields;
+        let indexes = (0..literals.len())
+            .map(|i| indexes.get(i).copied().unwrap_or(i))
+            .collect::<Vec<_>>();
+
+        while fields.len() <= indexes.iter().copied().max().unwrap_or(0) {
+            let name = format!("column_{}", fields.len() + 1);
+            fields.push(Field::new(name, DataType::Null, true));
+        }
+
+        if self.scanning {
+            return;
+        }
+
+        let mut values = vec![Value::Null; fields.len()];
+
+        for (literal, index) in literals.into_iter().zip(indexes) {
+            values[index] = to_value(literal, &fields[index].data_type);
+        }
+
+        self.rows.push_back((table, values));
+    }
+
+    /// Sets the fields declared by `CREATE TABLE`, keeping the columns added
+    /// by the rows before it unless they have the same name as a declared
+    /// one, or the same position for the columns named after it.
+    fn set_fields(&mut self, table: usize, mut fields: Vec<Field>) {
+        let old = std::mem::take(&mut self.tables[table].fields);
+        let declared = fields.len();
+
+        for (i, field) in old.into_iter().enumerate() {
+            let is_declared = fields.iter().any(|f| f.name == field.name);
+            let is_positional = field.name == format!("column_{}", i + 1) && i < declared;
+
+            if !is_declared && !is_positional {
+                fields.push(field);
+            }
+        }
+
+        self.tables[table].fields = fields;
+    }
+
+    /// Reads the next statement, or line of a `COPY` block, returning
+    /// whether there was one.
+    fn statement(&mut self, lexer: &mut Lexer) -> Result<bool> {
+        if let Some((table, indexes)) = self.copy.take() {
+            if let Some(line) = lexer.copy_line()? {
+                let literals = line
+                    .into_iter()
+                    .map(|field| field.map_or(Literal::Null, Literal::Text))
+                    .collect();
+                self.push_row(table, &indexes, literals);
+                self.copy = Some((table, indexes));
+            }
+            return Ok(true);
+        }
+
+        let Some(token) = lexer.next()? else {
+            return Ok(false);
+        };
+
+        if token.is_word("CREATE") {
+            self.create_table(lexer)?;
+        } else if token.is_word("INSERT") || token.is_word("REPLACE") {
+            self.insert(lexer)?;
+        } else if token.is_word("COPY") {
+            self.copy(lexer)?;
+        } else if token != Token::Punct(';') {
+            lexer.skip_statement()?;
+        }
+
+        Ok(true)
+    }
+
+    fn create_table(&mut self, lexer: &mut Lexer) -> Result<()> {
+        // The tables are known once scanned
+        if !self.scanning {
+            return lexer.skip_statement();
+        }
+
+        while lexer.next_is_word("TEMPORARY")?
+            || lexer.next_is_word("TEMP")?
+            || lexer.next_is_word("UNLOGGED")?
+        {}
+
+        if !lexer.next_is_word("TABLE")? {
+            return lexer.skip_statement();
+        }
+
+        if lexer.next_is_word("IF")? {
+            lexer.next_is_word("NOT")?;
+            lexer.next_is_word("EXISTS")?;
+        }
+
+        let Some(name) = lexer.qualified_name()? else {
+            return lexer.skip_statement();
+        };
+
+        // CREATE TABLE ... AS SELECT
+        if !lexer.next_is(&Token::Punct('('))? {
+            return lexer.skip_statement();
+        }
+
+        let mut fields = vec![];
+        let mut tokens = vec![];
+        let mut depth = 0;
+
+        loop {
+            match lexer.next()? {
+                Some(Token::Punct(c @ (',' | ')'))) if depth == 0 => {
+                    let definition = std::mem::take(&mut tokens);
+
+                    if !matches!(definition.first(), Some(t) if is_constraint(t)) {
+                        fields.extend(column_definition(definition));
+                    }
+
+                    if c == ')' {
+                        break;
+                    }
+                }
+                Some(token) => {
+                    match token {
+                        Token::Punct('(') => depth += 1,
+                        Token::Punct(')') => depth -= 1,
+                        _ => {}
+                    }
+                    tokens.push(token);
+                }
+                None => return lexer.error("Unterminated CREATE TABLE"),
+            }
+        }
+
+        lexer.skip_statement()?;
+
+        let index = self.table_index(&name);
+        self.set_fields(index, fields);
+
+        Ok(())
+    }
+
+    fn insert(&mut self, lexer: &mut Lexer) -> Result<()> {
+        // INSERT IGNORE INTO, INSERT OR REPLACE INTO, ...
+        loop {
+            match lexer.next()? {
+                Some(t) if t.is_word("INTO") => break,
+                Some(Token::Word(_)) => {}
+                _ => return lexer.error("Expected INTO"),
+            }
+        }
+
+        let Some(name) = lexer.qualified_name()? else {
+            return lexer.error("Expected a table name");
+        };
+        let columns = lexer.column_list()?;
+
+        if !(lexer.next_is_word("VALUES")? || lexer.next_is_word("VALUE")?)
+            || !self.is_wanted(&name)
+        {
+            return lexer.skip_statement();
+        }
+
+        let table = self.table_index(&name);
+        let indexes = self.column_indexes(table, columns);
+
+        loop {
+            if !lexer.next_is(&Token::Punct('('))? {
+                return lexer.error("Expected `(`");
+            }
+
+            let mut literals = vec![];
+            loop {
+                literals.push(lexer.literal()?);
+                match lexer.next()? {
+                    Some(Token::Punct(',')) => {}
+                    Some(Token::Punct(')')) => break,
+                    _ => return lexer.error("Expected `,` or `)`"),
+                }
+            }
+
+            self.push_row(table, &indexes, literals);
+
+            if !lexer.next_is(&Token::Punct(','))? {
+                break;
+            }
+        }
+
+        // ON CONFLICT, ON DUPLICATE KEY UPDATE, RETURNING, ...
+        lexer.skip_statement()
+    }
+
+    /// Starts a `COPY ... FROM stdin` block, its lines are then read one at a
+    /// time by `statement`.
+    fn copy(&mut self, lexer: &mut Lexer) -> Result<()> {
+        let Some(name) = lexer.qualified_name()? else {
+            return lexer.skip_statement();
+        };
+        let columns = lexer.column_list()?;
+
+        if !(lexer.next_is_word("FROM")? && lexer.next_is_word("STDIN")?) {
+            return lexer.skip_statement();
+        }
+        lexer.skip_statement()?;
+        lexer.copy_start();
+
+        if self.is_wanted(&name) {
+            let table = self.table_index(&name);
+            let indexes = self.column_indexes(table, columns);
+            self.copy = Some((table, indexes));
+        } else {
+            while lexer.copy_line()?.is_some() {}
+        }
+
+        Ok(())
+    }
+}
+
+impl Reader {
+    pub fn new<T: AsRef<[u8]>>(data: T, options: Options) -> Result<Self> {
+        let text = String::from_utf8_lossy(data.as_ref()).into_owned();
+        let syntax = options.syntax.unwrap_or_else(|| detect_syntax(&text));
+
+        let mut parser = Parser {
+            only_table: options.table,
+            tables: vec![],
+            scanning: true,
+            rows: VecDeque::new(),
+            copy: None,
+        };
+
+        let mut lexer = Lexer::new(&text, syntax);
+        while parser.statement(&mut lexer)? {}
+        parser.scanning = false;
+
+        if let Some(table) = &parser.only_table {
+            if !parser.tables.iter().any(|t| &t.name == table) {
+                return Err(Error::InputError(format!("Table `{}` not found", table)));
+            }
+        }
+
+        Ok(Self {
+            text,
+            syntax,
+            pos: 0,
+            parser,
+            table_column: options.table_column,
+        })
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.parser.tables
+    }
+}
+
+impl reader::ValueReader for Reader {
+    fn reset(&mut self) {
+        self.pos = 0;
+        self.parser.rows.clear();
+        self.parser.copy = None;
+    }
+
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        while self.parser.rows.is_empty() {
+            let mut lexer = Lexer::new(&self.text, self.syntax);
+            lexer.pos = self.pos;
+            let found = self.parser.statement(&mut lexer)?;
+            self.pos = lexer.pos;
+
+            if !found {
+                return Ok(None);
+            }
+        }
+
+        let Some((table, values)) = self.parser.rows.pop_front() else {
+            return Ok(None);
+        };
+        let table = &self.parser.tables[table];
+
+        let mut row = Object::with_capacity(table.fields.len() + 1);
+
+        if let Some(column) = &self.table_column {
+            row.insert(
+                Cow::Borrowed(column.as_str()),
+                Value::String(Cow::Borrowed(table.name.as_str())),
+            );
+        }
+
+        let mut values = values.into_iter();
+        for field in &table.fields {
+            row.insert(
+                Cow::Borrowed(field.name.as_str()),
+                values.next().unwrap_or(Value::Null),
+            );
+        }
+
+        Ok(Some(Value::Object(row)))
+    }
+}
+
+#[test]
+fn sql_dumps() {
+    use crate::reader::ValueReader;
+
+    let postgres = "
+-- Dumped by pg_dump version 15.2
+SET statement_timeout = 0;
+
+CREATE TABLE public.users (
+    id integer NOT NULL,
+    name character varying(64),
+    active boolean DEFAULT true,
+    score double precision,
+    meta jsonb,
+    CONSTRAINT users_pkey PRIMARY KEY (id)
+);
+
+COPY public.users (id, name, active, score, meta) FROM stdin;
+1\tAl\\tice\tt\t1.5\t{\"a\": 1}
+2\t\\N\tf\t\\N\t\\N
+\\.
+
+INSERT INTO public.users VALUES (3, 'O''Brien', false, -2, '{\"b\": [true]}'::jsonb), (4, 'x; y', NULL, 1e3, NULL);
+";
+
+    let mut reader = Reader::new(postgres, Default::default()).unwrap();
+
+    assert_eq!(reader.tables()[0].name, "public.users");
+    assert_eq!(reader.tables()[0].fields[1].data_type, DataType::String);
+    assert!(!reader.tables()[0].fields[0].is_nullable);
+
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 1, "name": "Al\tice", "active": true, "score": 1.5, "meta": {"a": 1}})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 2, "name": null, "active": false, "score": null, "meta": null})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 3, "name": "O'Brien", "active": false, "score": -2.0, "meta": {"b": [true]}})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"id": 4, "name": "x; y", "active": null, "score": 1000.0, "meta": null})
+    );
+    assert!(reader.next().unwrap().is_none());
+
+    let mysql = "
+/*!40101 SET NAMES utf8mb4 */;
+CREATE TABLE `t` (
+  `id` int NOT NULL AUTO_INCREMENT,
+  `flag` tinyint(1) DEFAULT NULL,
+  `data` blob,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB;
+INSERT INTO `t` (`flag`, `id`, `data`) VALUES (1,1,0x4142),(0,2,'it\\'s');
+INSERT INTO `other` VALUES ('skipped');
+";
+
+    let mut reader = Reader::new(
+        mysql,
+        Options {
+            table: Some("t".into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        Value::Object(
+            vec![
+                ("id".into(), Value::Int(1)),
+                ("flag".into(), Value::Bool(true)),
+                ("data".into(), Value::Binary(b"AB".to_vec())),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        Value::Object(
+            vec![
+                ("id".into(), Value::Int(2)),
+                ("flag".into(), Value::Bool(false)),
+                ("data".into(), Value::Binary(b"it's".to_vec())),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+    assert!(reader.next().unwrap().is_none());
+}
+
+#[test]
+fn sql_dump_expressions() {
+    use crate::reader::ValueReader;
+
+    let read = |dump: &str| {
+        let mut reader = Reader::new(dump, Default::default()).unwrap();
+        let mut rows = vec![];
+        while let Some(row) = reader.next().unwrap() {
+            rows.push(row.into_owned());
+        }
+        rows
+    };
+    let row = |values: Vec<(&'static str, Value<'static>)>| {
+        Value::Object(
+            values
+                .into_iter()
+                .map(|(k, v)| (Cow::Borrowed(k), v))
+                .collect(),
+        )
+    };
+    let string = |s: &'static str| Value::String(Cow::Borrowed(s));
+
+    // `::1` in the data doesn't make it a Postgres dump
+    assert_eq!(
+        read(r"INSERT INTO `t` VALUES ('::1','it\'s',_binary 'a\0b',_utf8mb4'x');"),
+        vec![row(vec![
+            ("column_1", string("::1")),
+            ("column_2", string("it's")),
+            ("column_3", Value::Binary(b"a\0b".to_vec())),
+            ("column_4", string("x")),
+        ])]
+    );
+
+    assert_eq!(
+        read(
+            r"PRAGMA foreign_keys=OFF;
+INSERT INTO t VALUES(replace(replace('a\r\nb','\r',char(13)),'\n',char(10)),'x' || char(0, 65));
+CREATE TABLE t (s TEXT, n TEXT);"
+        ),
+        vec![row(vec![("s", string("a\r\nb")), ("n", string("x\0A"))])]
+    );
+
+    assert_eq!(
+        read("-- PostgreSQL database dump\nINSERT INTO t (a) VALUES (E'\\xc3\\xa9'), (E'\\xff');"),
+        vec![
+            row(vec![("a", string("\u{e9}"))]),
+            row(vec![("a", Value::Binary(vec![0xff]))])
+        ]
+    );
+}
+
+#[test]
+fn sql_dump_schemas() {
+    use crate::reader::ValueReader;
+
+    let dump = "INSERT INTO a.t VALUES (1);
+INSERT INTO b.t VALUES ('x');
+CREATE TABLE b.t (name TEXT);
+INSERT INTO b.t VALUES ('y');";
+
+    let mut reader = Reader::new(
+        dump,
+        Options {
+            table: Some("b.t".into()),
+            table_column: Some("table".into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"table": "b.t", "name": "x"})
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"table": "b.t", "name": "y"})
+        );
+        assert!(reader.next().unwrap().is_none());
+        reader.reset();
+    }
+}
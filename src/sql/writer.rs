@@ -1,9 +1,12 @@
-use crate::reader::ValueReader;
-use crate::{DataType, Field, Result, Value};
+use crate::io::{Encoding, EncodingWriter, Unmappable};
+use crate::reader::{RowIterator, ValueReader};
+use crate::{normalize, DataType, Error, Field, Result, Value};
 
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::io::Write;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
 pub enum Syntax {
     #[serde(rename = "postgres")]
     Postgres,
@@ -11,41 +14,294 @@ pub enum Syntax {
     Mysql,
     #[serde(rename = "sqlite")]
     Sqlite,
+    #[serde(rename = "sqlserver")]
+    SqlServer,
+    #[serde(rename = "oracle")]
+    Oracle,
+    #[serde(rename = "clickhouse")]
+    ClickHouse,
+    #[serde(rename = "bigquery")]
+    BigQuery,
+    #[serde(rename = "snowflake")]
+    Snowflake,
 }
 
-#[derive(Debug, serde::Deserialize)]
+impl Syntax {
+    /// Whether backslashes start escape sequences in string literals.
+    pub fn backslash_escapes(&self) -> bool {
+        matches!(
+            self,
+            Syntax::Mysql | Syntax::ClickHouse | Syntax::BigQuery | Syntax::Snowflake
+        )
+    }
+
+    /// Most rows a single `INSERT` statement can hold.
+    fn max_rows(&self) -> Option<usize> {
+        match self {
+            Syntax::SqlServer | Syntax::Oracle => Some(1000),
+            Syntax::Snowflake => Some(16384),
+            _ => None,
+        }
+    }
+
+    /// Longest statement accepted, in bytes.
+    fn max_statement_bytes(&self) -> Option<usize> {
+        match self {
+            Syntax::BigQuery | Syntax::Snowflake => Some(1024 * 1024),
+            _ => None,
+        }
+    }
+}
+
+/// What to do with rows clashing with an existing primary key or unique
+/// constraint.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub enum OnConflict {
+    /// `ON CONFLICT DO NOTHING`, or `INSERT IGNORE` in MySQL
+    #[serde(rename = "ignore")]
+    Ignore,
+    /// `ON CONFLICT (...) DO UPDATE`, or `ON DUPLICATE KEY UPDATE` in MySQL
+    #[serde(rename = "update")]
+    Update,
+    /// `REPLACE INTO`, which Postgres doesn't have so it updates instead
+    #[serde(rename = "replace")]
+    Replace,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, serde::Deserialize)]
+pub enum Mode {
+    #[default]
+    #[serde(rename = "insert")]
+    Insert,
+    /// Postgres `COPY ... FROM stdin` block in text format
+    #[serde(rename = "copy")]
+    Copy,
+    /// MySQL `LOAD DATA` statement, the rows go to a tab-separated `data_file`
+    #[serde(rename = "load_data")]
+    LoadData,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Options {
     pub syntax: Syntax,
     pub create_table: bool,
     pub table_name: String,
+    /// Maximum number of rows per `INSERT` statement, all rows go in a single
+    /// statement when missing
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// Writes each row in its own single-line `INSERT` statement
+    #[serde(default)]
+    pub statement_per_row: bool,
+    /// Wraps the statements in `BEGIN;` and `COMMIT;`
+    #[serde(default)]
+    pub transaction: bool,
+    /// Lists the column names in every `INSERT` statement
+    #[serde(default)]
+    pub column_names: bool,
+    #[serde(default)]
+    pub on_conflict: Option<OnConflict>,
+    /// Columns of the constraint the conflicts are detected on, required to
+    /// update rows in Postgres and SQLite
+    #[serde(default)]
+    pub conflict_columns: Vec<String>,
+    #[serde(default)]
+    pub mode: Mode,
+    /// Path of the file written in `load_data` mode
+    #[serde(default)]
+    pub data_file: Option<String>,
+    /// Schema, or dataset, the table belongs to
+    #[serde(default)]
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub if_not_exists: bool,
+    /// Writes `DROP TABLE IF EXISTS` before creating the table, or its PL/SQL
+    /// equivalent for Oracle
+    #[serde(default)]
+    pub drop_table: bool,
+    #[serde(default)]
+    pub primary_key: Vec<String>,
+    /// Uses the first non-null column with unique values as primary key when
+    /// `primary_key` is empty
+    #[serde(default)]
+    pub infer_primary_key: bool,
+    /// Columns to create an index on
+    #[serde(default)]
+    pub indexes: Vec<String>,
+    /// Moves arrays of objects to child tables referencing their parent row
+    /// through generated keys
+    #[serde(default)]
+    pub normalize: Option<normalize::Options>,
+    /// Output encoding, UTF-8 when not set
+    #[serde(default)]
+    pub encoding: Option<Encoding>,
+    #[serde(default)]
+    pub unmappable: Unmappable,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            syntax: Syntax::Postgres,
+            create_table: true,
+            table_name: String::new(),
+            batch_size: None,
+            statement_per_row: false,
+            transaction: false,
+            column_names: false,
+            on_conflict: None,
+            conflict_columns: vec![],
+            mode: Mode::default(),
+            data_file: None,
+            schema: None,
+            if_not_exists: false,
+            drop_table: false,
+            primary_key: vec![],
+            infer_primary_key: false,
+            indexes: vec![],
+            normalize: None,
+            encoding: None,
+            unmappable: Unmappable::default(),
+        }
+    }
 }
 
-pub fn write_value<W: Write>(writer: &mut W, buf: &mut String, value: &Value) -> Result<()> {
-    match value {
-        Value::Int(i) => write!(writer, "{}", i),
-        Value::String(s) => write!(writer, "\'{}\'", s.replace('\'', "''")),
-        Value::Float(s) => write!(writer, "{}", s),
-        Value::Bool(true) => writer.write_all(b"TRUE"),
-        Value::Bool(false) => writer.write_all(b"FALSE"),
-        Value::Object(o) => {
-            unsafe { serde_json::to_writer(buf.as_mut_vec(), &o)? };
-            write!(writer, "\'{}\'", buf.replace('\'', "''"))
+/// A column referencing the key of another table.
+pub struct ForeignKey<'a> {
+    pub column: &'a str,
+    pub table: &'a str,
+    pub references: &'a str,
+}
+
+/// Writes a string literal, `'` being doubled.
+fn write_quoted<W: Write>(writer: &mut W, prefix: &str, s: &str) -> std::io::Result<()> {
+    write!(writer, "{}'{}'", prefix, s.replace('\'', "''"))
+}
+
+/// Writes a string literal with backslash escapes, control characters are
+/// written as `\xHH` unless `mysql` is set, which only knows a few escapes.
+fn write_backslash_escaped<W: Write>(
+    writer: &mut W,
+    prefix: &str,
+    s: &str,
+    mysql: bool,
+) -> std::io::Result<()> {
+    write!(writer, "{}'", prefix)?;
+
+    for c in s.chars() {
+        match c {
+            '\\' => writer.write_all(b"\\\\")?,
+            '\'' => writer.write_all(b"\\'")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            '\t' => writer.write_all(b"\\t")?,
+            '\0' if mysql => writer.write_all(b"\\0")?,
+            '\x1a' if mysql => writer.write_all(b"\\Z")?,
+            c if c.is_ascii_control() && !mysql => write!(writer, "\\x{:02x}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+
+    writer.write_all(b"'")
+}
+
+fn write_string<W: Write>(writer: &mut W, syntax: &Syntax, s: &str) -> Result<()> {
+    match syntax {
+        Syntax::Mysql => write_backslash_escaped(writer, "", s, true)?,
+        Syntax::ClickHouse | Syntax::BigQuery | Syntax::Snowflake => {
+            write_backslash_escaped(writer, "", s, false)?
+        }
+        Syntax::Postgres if s.contains('\0') => {
+            return Err(Error::OutputError(
+                "Postgres strings can't contain NUL characters".into(),
+            ))
+        }
+        // Escape strings keep control characters readable and intact
+        Syntax::Postgres if s.contains(|c: char| c.is_ascii_control()) => {
+            write_backslash_escaped(writer, "E", s, false)?
+        }
+        Syntax::Postgres => write_quoted(writer, "", s)?,
+        Syntax::Sqlite | Syntax::Oracle | Syntax::SqlServer => {
+            let (prefix, concat, nul) = match syntax {
+                Syntax::Sqlite => ("", " || ", "char(0)"),
+                Syntax::Oracle => ("", " || ", "CHR(0)"),
+                _ => ("N", " + ", "NCHAR(0)"),
+            };
+
+            // NUL characters would end the literal, they are concatenated instead
+            for (i, part) in s.split('\0').enumerate() {
+                if i > 0 {
+                    write!(writer, "{}{}{}", concat, nul, concat)?;
+                }
+                write_quoted(writer, prefix, part)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_value<W: Write>(
+    writer: &mut W,
+    buf: &mut String,
+    value: &Value,
+    syntax: &Syntax,
+) -> Result<()> {
+    match (value, syntax) {
+        (Value::Int(i), _) => write!(writer, "{}", i),
+        (Value::String(s), _) => return write_string(writer, syntax, s),
+        (Value::Float(s), _) => write!(writer, "{}", s),
+        (Value::Bool(b), Syntax::SqlServer | Syntax::Oracle) => {
+            writer.write_all(if *b { b"1" } else { b"0" })
+        }
+        (Value::Bool(true), _) => writer.write_all(b"TRUE"),
+        (Value::Bool(false), _) => writer.write_all(b"FALSE"),
+        (Value::Object(_) | Value::Array(_), _) => {
+            unsafe { serde_json::to_writer(buf.as_mut_vec(), value)? };
+            if *syntax == Syntax::BigQuery {
+                writer.write_all(b"JSON ")?;
+            }
+            return write_string(writer, syntax, buf);
+        }
+        (Value::Null, _) => write!(writer, "NULL"),
+        (Value::Binary(b), Syntax::Postgres) => {
+            write!(writer, "'\\x{}'::bytea", hex::encode(b))
+        }
+        (Value::Binary(b), Syntax::SqlServer) => write!(writer, "0x{}", hex::encode(b)),
+        (Value::Binary(b), Syntax::Oracle) => write!(writer, "HEXTORAW('{}')", hex::encode(b)),
+        (Value::Binary(b), Syntax::ClickHouse) => {
+            write!(writer, "unhex('{}')", hex::encode(b))
         }
-        Value::Array(o) => {
-            unsafe { serde_json::to_writer(buf.as_mut_vec(), &o)? };
-            write!(writer, "\'{}\'", buf.replace('\'', "''"))
+        (Value::Binary(b), Syntax::BigQuery) => {
+            write!(writer, "FROM_HEX('{}')", hex::encode(b))
         }
-        Value::Null => write!(writer, "NULL"),
-        Value::Binary(_) => todo!(),
+        (Value::Binary(b), Syntax::Snowflake) => {
+            write!(writer, "TO_BINARY('{}', 'HEX')", hex::encode(b))
+        }
+        (Value::Binary(b), _) => write!(writer, "X'{}'", hex::encode(b)),
     }
     .map_err(Into::into)
 }
 
-fn write_identifier<W: Write>(writer: &mut W, syntax: &Syntax, id: &str) -> Result<()> {
+pub fn write_identifier<W: Write>(writer: &mut W, syntax: &Syntax, id: &str) -> Result<()> {
+    if id.contains('\0') {
+        return Err(Error::OutputError(format!(
+            "Identifier {:?} contains a NUL character",
+            id
+        )));
+    }
+
     match syntax {
-        Syntax::Mysql => write!(writer, "`{}`", id),
-        Syntax::Postgres => write!(writer, "\"{}\"", id),
-        Syntax::Sqlite => write!(writer, "[{}]", id),
+        Syntax::Mysql => write!(writer, "`{}`", id.replace('`', "``")),
+        Syntax::ClickHouse | Syntax::BigQuery => {
+            write!(writer, "`{}`", id.replace('\\', "\\\\").replace('`', "\\`"))
+        }
+        // Brackets can't be escaped, double quotes can
+        Syntax::Sqlite if !id.contains(']') => write!(writer, "[{}]", id),
+        Syntax::Postgres | Syntax::Oracle | Syntax::Snowflake | Syntax::Sqlite => {
+            write!(writer, "\"{}\"", id.replace('"', "\"\""))
+        }
+        Syntax::SqlServer => write!(writer, "[{}]", id.replace(']', "]]")),
     }
     .map_err(Into::into)
 }
@@ -82,80 +338,1358 @@ fn data_type(syntax: &Syntax, dt: &DataType) -> &'static str {
             DataType::Binary => "BLOB",
             DataType::Null => data_type(syntax, &DataType::String),
         },
+        Syntax::SqlServer => match dt {
+            DataType::Object(_) => "NVARCHAR(MAX)",
+            DataType::Array(_) => "NVARCHAR(MAX)",
+            DataType::Int => "BIGINT",
+            DataType::Float => "FLOAT",
+            DataType::Bool => "BIT",
+            DataType::String => "NVARCHAR(MAX)",
+            DataType::Binary => "VARBINARY(MAX)",
+            DataType::Null => data_type(syntax, &DataType::String),
+        },
+        Syntax::Oracle => match dt {
+            DataType::Object(_) => "CLOB",
+            DataType::Array(_) => "CLOB",
+            DataType::Int => "NUMBER(19)",
+            DataType::Float => "BINARY_DOUBLE",
+            DataType::Bool => "NUMBER(1)",
+            DataType::String => "VARCHAR2(4000)",
+            DataType::Binary => "BLOB",
+            DataType::Null => data_type(syntax, &DataType::String),
+        },
+        Syntax::ClickHouse => match dt {
+            DataType::Object(_) => "String",
+            DataType::Array(_) => "String",
+            DataType::Int => "Int64",
+            DataType::Float => "Float64",
+            DataType::Bool => "Bool",
+            DataType::String => "String",
+            DataType::Binary => "String",
+            DataType::Null => data_type(syntax, &DataType::String),
+        },
+        Syntax::BigQuery => match dt {
+            DataType::Object(_) => "JSON",
+            DataType::Array(_) => "JSON",
+            DataType::Int => "INT64",
+            DataType::Float => "FLOAT64",
+            DataType::Bool => "BOOL",
+            DataType::String => "STRING",
+            DataType::Binary => "BYTES",
+            DataType::Null => data_type(syntax, &DataType::String),
+        },
+        Syntax::Snowflake => match dt {
+            DataType::Object(_) => "VARIANT",
+            DataType::Array(_) => "VARIANT",
+            DataType::Int => "BIGINT",
+            DataType::Float => "FLOAT",
+            DataType::Bool => "BOOLEAN",
+            DataType::String => "VARCHAR",
+            DataType::Binary => "BINARY",
+            DataType::Null => data_type(syntax, &DataType::String),
+        },
     }
 }
 
+/// Longest MySQL key on strings, 3072 bytes with 4 bytes per character.
+/// Longer key columns are `TEXT` indexed on a prefix of this length.
+const MYSQL_KEY_LENGTH: usize = 768;
+
+/// Column type sized after the longest string of the column, `is_key` for the
+/// columns in the primary key or an index.
+fn column_type(
+    syntax: &Syntax,
+    dt: &DataType,
+    max_length: usize,
+    is_key: bool,
+) -> Cow<'static, str> {
+    let length = max_length.max(1);
+
+    match (syntax, dt) {
+        // Keys can't be on a whole TEXT column
+        (Syntax::Mysql, DataType::String | DataType::Null)
+            if is_key && length <= MYSQL_KEY_LENGTH =>
+        {
+            format!("VARCHAR({})", length).into()
+        }
+        // Longer ones soon go over the row size limit of InnoDB
+        (Syntax::Mysql, DataType::String | DataType::Null) if length <= 255 => {
+            format!("VARCHAR({})", length).into()
+        }
+        // 64 KB, with 4 bytes per character
+        (Syntax::Mysql, DataType::String | DataType::Null) if length <= 16383 => "TEXT".into(),
+        (Syntax::Mysql, DataType::String | DataType::Null) => "LONGTEXT".into(),
+        (Syntax::SqlServer, DataType::String | DataType::Null) if length <= 4000 => {
+            format!("NVARCHAR({})", length).into()
+        }
+        (Syntax::Oracle, DataType::String | DataType::Null) if length <= 4000 => {
+            format!("VARCHAR2({} CHAR)", length).into()
+        }
+        (Syntax::Oracle, DataType::String | DataType::Null) => "CLOB".into(),
+        _ => data_type(syntax, dt).into(),
+    }
+}
+
+fn write_qualified_name<W: Write>(mut writer: &mut W, opts: &Options, name: &str) -> Result<()> {
+    if let Some(schema) = &opts.schema {
+        write_identifier(&mut writer, &opts.syntax, schema)?;
+        writer.write_all(b".")?;
+    }
+    write_identifier(&mut writer, &opts.syntax, name)
+}
+
+fn write_table_name<W: Write>(writer: &mut W, opts: &Options) -> Result<()> {
+    write_qualified_name(writer, opts, &opts.table_name)
+}
+
+/// Writes `DROP TABLE IF EXISTS`, or for Oracle before 23c a PL/SQL block
+/// ignoring the error of a missing table (ORA-00942).
+fn write_drop_table<W: Write>(mut writer: W, opts: &Options) -> Result<()> {
+    if opts.syntax != Syntax::Oracle {
+        writer.write_all(b"DROP TABLE IF EXISTS ")?;
+        write_table_name(&mut writer, opts)?;
+        writer.write_all(b";\n")?;
+        return Ok(());
+    }
+
+    let mut statement = b"DROP TABLE ".to_vec();
+    write_table_name(&mut statement, opts)?;
+
+    writer.write_all(b"BEGIN\n  EXECUTE IMMEDIATE ")?;
+    write_string(
+        &mut writer,
+        &opts.syntax,
+        &String::from_utf8_lossy(&statement),
+    )?;
+    writer.write_all(
+        b";\nEXCEPTION\n  WHEN OTHERS THEN\n    IF SQLCODE != -942 THEN\n      RAISE;\n    END IF;\nEND;\n/\n",
+    )?;
+
+    Ok(())
+}
+
+/// Writes `CREATE TABLE` for `fields`, with `max_lengths` holding the length
+/// of the longest string of each field.
 pub fn write_create_table<W: Write>(
     mut writer: &mut W,
-    syntax: &Syntax,
-    table_name: &str,
-    fields: &Vec<Field>,
+    opts: &Options,
+    fields: &[Field],
+    max_lengths: &[usize],
+    primary_key: &[String],
+    foreign_key: Option<&ForeignKey>,
 ) -> Result<()> {
-    let mut is_first = true;
-    write!(writer, "CREATE TABLE ")?;
-    write_identifier(&mut writer, syntax, table_name)?;
+    let syntax = &opts.syntax;
+
+    let unknown = |columns: &[String], option: &str| match columns
+        .iter()
+        .find(|c| !fields.iter().any(|f| &f.name == *c))
+    {
+        Some(column) => Err(Error::OutputError(format!(
+            "Unknown column `{}` in `{}`",
+            column, option
+        ))),
+        None => Ok(()),
+    };
+    unknown(primary_key, "primary_key")?;
+    unknown(&opts.indexes, "indexes")?;
+
+    // Writes a key column, with the length of the prefix indexed for MySQL
+    // `TEXT` columns
+    let write_key_column = |writer: &mut W, column: &str| -> Result<()> {
+        write_identifier(writer, syntax, column)?;
+
+        let index = fields.iter().position(|f| f.name == column);
+        let is_text = match index {
+            Some(i) => {
+                matches!(fields[i].data_type, DataType::String | DataType::Null)
+                    && max_lengths.get(i).copied().unwrap_or(0) > MYSQL_KEY_LENGTH
+            }
+            None => false,
+        };
+        if *syntax == Syntax::Mysql && is_text {
+            write!(writer, "({})", MYSQL_KEY_LENGTH)?;
+        }
+
+        Ok(())
+    };
+
+    if opts.drop_table {
+        write_drop_table(&mut writer, opts)?;
+    }
+
+    match syntax {
+        Syntax::SqlServer if opts.if_not_exists => {
+            let mut name = vec![];
+            write_table_name(&mut name, opts)?;
+            write!(writer, "IF OBJECT_ID(")?;
+            write_string(&mut writer, syntax, &String::from_utf8_lossy(&name))?;
+            writeln!(writer, ", N'U') IS NULL")?;
+            write!(writer, "CREATE TABLE ")?;
+        }
+        _ if opts.if_not_exists => write!(writer, "CREATE TABLE IF NOT EXISTS ")?,
+        _ => write!(writer, "CREATE TABLE ")?,
+    }
+    write_table_name(&mut writer, opts)?;
     writeln!(writer, " (")?;
 
-    for field in fields {
-        if is_first {
-            is_first = false;
-        } else {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
             writer.write_all(b",\n")?;
         }
 
+        let max_length = max_lengths.get(i).copied().unwrap_or(0);
+        let is_key = primary_key.contains(&field.name) || opts.indexes.contains(&field.name);
+        let column_type = column_type(syntax, &field.data_type, max_length, is_key);
+        let is_nullable = field.is_nullable && !primary_key.contains(&field.name);
+
         writer.write_all(b"  ")?;
         write_identifier(&mut writer, syntax, &field.name)?;
         writer.write_all(b" ")?;
-        writer.write_all(data_type(syntax, &field.data_type).as_bytes())?;
-        if field.is_nullable {
-            writer.write_all(b" NULL")?;
+        match syntax {
+            // Columns aren't nullable unless their type is wrapped
+            Syntax::ClickHouse if is_nullable => write!(writer, "Nullable({})", column_type)?,
+            Syntax::ClickHouse => writer.write_all(column_type.as_bytes())?,
+            // BigQuery doesn't accept an explicit NULL
+            Syntax::BigQuery if is_nullable => writer.write_all(column_type.as_bytes())?,
+            _ if is_nullable => write!(writer, "{} NULL", column_type)?,
+            _ => write!(writer, "{} NOT NULL", column_type)?,
         }
     }
 
-    writer.write_all(b"\n);\n\n")?;
+    if !primary_key.is_empty() && *syntax != Syntax::ClickHouse {
+        writer.write_all(b",\n  PRIMARY KEY (")?;
+        for (i, column) in primary_key.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            write_key_column(writer, column)?;
+        }
+        writer.write_all(b")")?;
+        if *syntax == Syntax::BigQuery {
+            writer.write_all(b" NOT ENFORCED")?;
+        }
+    }
+
+    if let Some(foreign_key) = foreign_key.filter(|_| *syntax != Syntax::ClickHouse) {
+        writer.write_all(b",\n  FOREIGN KEY (")?;
+        write_identifier(&mut writer, syntax, foreign_key.column)?;
+        writer.write_all(b") REFERENCES ")?;
+        write_qualified_name(&mut writer, opts, foreign_key.table)?;
+        writer.write_all(b" (")?;
+        write_identifier(&mut writer, syntax, foreign_key.references)?;
+        writer.write_all(b")")?;
+        if *syntax == Syntax::BigQuery {
+            writer.write_all(b" NOT ENFORCED")?;
+        }
+    }
+
+    match syntax {
+        Syntax::ClickHouse if !primary_key.is_empty() => {
+            writer.write_all(b"\n) ENGINE = MergeTree ORDER BY (")?;
+            write_identifiers(&mut writer, syntax, primary_key)?;
+            writer.write_all(b");\n")?;
+        }
+        Syntax::ClickHouse => writer.write_all(b"\n) ENGINE = MergeTree ORDER BY tuple();\n")?,
+        _ => writer.write_all(b"\n);\n")?,
+    }
+
+    for column in &opts.indexes {
+        let index_name = format!("{}_{}_idx", opts.table_name, column);
+
+        if opts.if_not_exists && matches!(syntax, Syntax::Postgres | Syntax::Sqlite) {
+            writer.write_all(b"CREATE INDEX IF NOT EXISTS ")?;
+        } else {
+            writer.write_all(b"CREATE INDEX ")?;
+        }
+        write_identifier(&mut writer, syntax, &index_name)?;
+        writer.write_all(b" ON ")?;
+        write_table_name(&mut writer, opts)?;
+        writer.write_all(b" (")?;
+        write_key_column(writer, column)?;
+        writer.write_all(b");\n")?;
+    }
+
+    writer.write_all(b"\n")?;
 
     Ok(())
 }
 
-pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R, opts: Options) -> Result<()> {
-    let mut rows = reader.into_rows()?;
+/// Finds the longest string of each field, in UTF-16 units for SQL Server
+/// and characters otherwise, and, when `infer_primary_key` is set without an
+/// explicit key, the first non-null column with unique values.
+fn scan_rows<R: ValueReader>(
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<(Vec<usize>, Vec<String>)> {
+    let fields = rows.fields().clone();
+    let mut max_lengths = vec![0; fields.len()];
 
-    if opts.create_table {
-        write_create_table(&mut writer, &opts.syntax, &opts.table_name, rows.fields())?;
+    let infer_key = opts.infer_primary_key && opts.primary_key.is_empty();
+    // Values seen in each key candidate, None once the column can't be a key
+    let mut seen = fields
+        .iter()
+        .map(|f| {
+            let is_candidate = infer_key
+                && !f.is_nullable
+                && matches!(f.data_type, DataType::Int | DataType::String);
+            is_candidate.then(HashSet::new)
+        })
+        .collect::<Vec<_>>();
+
+    while let Some(row) = rows.next()? {
+        for (i, value) in row.enumerate() {
+            let key = match value? {
+                Value::String(s) => {
+                    let length = match opts.syntax {
+                        Syntax::SqlServer => s.encode_utf16().count(),
+                        _ => s.chars().count(),
+                    };
+                    max_lengths[i] = max_lengths[i].max(length);
+                    s.into_owned()
+                }
+                Value::Int(n) => n.to_string(),
+                _ => {
+                    seen[i] = None;
+                    continue;
+                }
+            };
+
+            if let Some(values) = &mut seen[i] {
+                if !values.insert(key) {
+                    seen[i] = None;
+                }
+            }
+        }
     }
 
-    writer.write_all(b"INSERT INTO ")?;
-    write_identifier(&mut writer, &opts.syntax, &opts.table_name)?;
-    writer.write_all(b" VALUES\n")?;
+    rows.reset();
 
-    let mut is_first_row = true;
-    let mut buf = String::new();
+    let primary_key = if infer_key {
+        fields
+            .iter()
+            .zip(&seen)
+            .find(|(_, seen)| matches!(seen, Some(values) if !values.is_empty()))
+            .map(|(f, _)| vec![f.name.clone()])
+            .unwrap_or_default()
+    } else {
+        opts.primary_key.clone()
+    };
 
-    while let Some(row) = rows.next()? {
-        let mut is_first_value = true;
+    Ok((max_lengths, primary_key))
+}
 
-        if is_first_row {
-            is_first_row = false;
-        } else {
-            writer.write_all(b",\n")?;
+fn write_identifiers<W: Write>(writer: &mut W, syntax: &Syntax, ids: &[String]) -> Result<()> {
+    for (i, id) in ids.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
         }
+        write_identifier(writer, syntax, id)?;
+    }
 
-        writer.write_all(b"(")?;
-        for value in row {
-            let value = value?;
+    Ok(())
+}
 
-            if is_first_value {
-                is_first_value = false;
-            } else {
+/// Rendering of `on_conflict` for the syntax of the output.
+fn on_conflict(opts: &Options) -> Option<OnConflict> {
+    match (opts.on_conflict, &opts.syntax) {
+        (Some(OnConflict::Replace), Syntax::Postgres) => Some(OnConflict::Update),
+        (on_conflict, _) => on_conflict,
+    }
+}
+
+fn write_table<W: Write>(mut writer: &mut W, opts: &Options, fields: &[Field]) -> Result<()> {
+    write_table_name(&mut writer, opts)?;
+
+    if opts.column_names {
+        let names = fields.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+        writer.write_all(b" (")?;
+        write_identifiers(&mut writer, &opts.syntax, &names)?;
+        writer.write_all(b")")?;
+    }
+
+    Ok(())
+}
+
+fn write_insert<W: Write>(mut writer: &mut W, opts: &Options, fields: &[Field]) -> Result<()> {
+    match (on_conflict(opts), &opts.syntax) {
+        (Some(OnConflict::Replace), _) => writer.write_all(b"REPLACE INTO ")?,
+        (Some(OnConflict::Ignore), Syntax::Mysql) => writer.write_all(b"INSERT IGNORE INTO ")?,
+        _ => writer.write_all(b"INSERT INTO ")?,
+    }
+    write_table(&mut writer, opts, fields)?;
+
+    let is_json = |f: &&Field| matches!(f.data_type, DataType::Object(_) | DataType::Array(_));
+
+    // Snowflake only parses JSON into VARIANT columns from a SELECT
+    if opts.syntax == Syntax::Snowflake && fields.iter().any(|f| is_json(&f)) {
+        writer.write_all(b" SELECT ")?;
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
                 writer.write_all(b",")?;
             }
+            if is_json(&field) {
+                write!(writer, "PARSE_JSON(column{})", i + 1)?;
+            } else {
+                write!(writer, "column{}", i + 1)?;
+            }
+        }
+        writer.write_all(b" FROM VALUES")?;
+    } else {
+        writer.write_all(b" VALUES")?;
+    }
+
+    Ok(())
+}
+
+/// Writes the clause following the rows of an `INSERT`.
+fn write_conflict_clause<W: Write>(
+    mut writer: &mut W,
+    opts: &Options,
+    fields: &[Field],
+) -> Result<()> {
+    let updated = fields
+        .iter()
+        .filter(|f| !opts.conflict_columns.contains(&f.name))
+        .collect::<Vec<_>>();
+
+    match (on_conflict(opts), &opts.syntax) {
+        (None | Some(OnConflict::Replace), _) | (Some(OnConflict::Ignore), Syntax::Mysql) => {}
+        (Some(OnConflict::Update), Syntax::Mysql) => {
+            // Without any other column, updating the key to itself ignores the row
+            let updated = if updated.is_empty() {
+                fields.iter().take(1).collect()
+            } else {
+                updated
+            };
+
+            writer.write_all(b" ON DUPLICATE KEY UPDATE ")?;
+            for (i, field) in updated.iter().enumerate() {
+                if i > 0 {
+                    writer.write_all(b",")?;
+                }
+                write_identifier(&mut writer, &opts.syntax, &field.name)?;
+                writer.write_all(b"=VALUES(")?;
+                write_identifier(&mut writer, &opts.syntax, &field.name)?;
+                writer.write_all(b")")?;
+            }
+        }
+        (Some(on_conflict), _) => {
+            writer.write_all(b" ON CONFLICT")?;
+            if !opts.conflict_columns.is_empty() {
+                writer.write_all(b" (")?;
+                write_identifiers(&mut writer, &opts.syntax, &opts.conflict_columns)?;
+                writer.write_all(b")")?;
+            }
+
+            if on_conflict == OnConflict::Ignore || updated.is_empty() {
+                writer.write_all(b" DO NOTHING")?;
+            } else {
+                writer.write_all(b" DO UPDATE SET ")?;
+                for (i, field) in updated.iter().enumerate() {
+                    if i > 0 {
+                        writer.write_all(b",")?;
+                    }
+                    write_identifier(&mut writer, &opts.syntax, &field.name)?;
+                    writer.write_all(b"=excluded.")?;
+                    write_identifier(&mut writer, &opts.syntax, &field.name)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_inserts<W: Write, R: ValueReader>(
+    mut writer: W,
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<()> {
+    let batch_size = if opts.statement_per_row {
+        1
+    } else {
+        opts.batch_size.unwrap_or(usize::MAX).max(1)
+    };
+    let max_rows = batch_size.min(opts.syntax.max_rows().unwrap_or(usize::MAX));
+    let max_bytes = opts.syntax.max_statement_bytes().unwrap_or(usize::MAX);
+    let row_separator: &[u8] = if opts.statement_per_row { b" " } else { b"\n" };
+
+    let fields = rows.fields().clone();
+
+    // Oracle has no multi-row VALUES, rows are inserted by `INSERT ALL INTO ...`
+    let insert_all = opts.syntax == Syntax::Oracle && batch_size > 1;
+
+    let mut header = vec![];
+    let mut row_prefix = vec![];
+    let mut footer = vec![];
+
+    if insert_all {
+        header.extend_from_slice(b"INSERT ALL");
+        row_prefix.extend_from_slice(b"\nINTO ");
+        write_table(&mut row_prefix, opts, &fields)?;
+        row_prefix.extend_from_slice(b" VALUES ");
+        footer.extend_from_slice(b"\nSELECT 1 FROM DUAL");
+    } else {
+        write_insert(&mut header, opts, &fields)?;
+        write_conflict_clause(&mut footer, opts, &fields)?;
+    }
+    footer.extend_from_slice(b";\n");
+
+    // Rows and bytes written in the current statement
+    let mut count = 0;
+    let mut statement_bytes = 0;
+    let mut buf = String::new();
+    let mut row_buf = vec![];
+
+    while let Some(row) = rows.next()? {
+        row_buf.clear();
+        row_buf.extend_from_slice(&row_prefix);
+        row_buf.push(b'(');
+        for (i, value) in row.enumerate() {
+            if i > 0 {
+                row_buf.push(b',');
+            }
             buf.clear();
-            write_value(&mut writer, &mut buf, &value)?;
+            write_value(&mut row_buf, &mut buf, &value?, &opts.syntax)?;
         }
-        writer.write_all(b")")?;
+        row_buf.push(b')');
+
+        if count > 0
+            && (count >= max_rows || statement_bytes + row_buf.len() + 2 + footer.len() > max_bytes)
+        {
+            writer.write_all(&footer)?;
+            count = 0;
+        }
+
+        if count == 0 {
+            writer.write_all(&header)?;
+            statement_bytes = header.len();
+        } else if !insert_all {
+            writer.write_all(b",")?;
+            statement_bytes += 1;
+        }
+
+        if !insert_all {
+            writer.write_all(row_separator)?;
+            statement_bytes += 1;
+        }
+
+        writer.write_all(&row_buf)?;
+        statement_bytes += row_buf.len();
+        count += 1;
+    }
+
+    if count > 0 {
+        writer.write_all(&footer)?;
+    }
+
+    Ok(())
+}
+
+/// Writes bytes with the backslash escapes of the Postgres `COPY` and MySQL
+/// `LOAD DATA` text formats.
+fn write_escaped<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+    let mut start = 0;
+
+    for (i, b) in bytes.iter().enumerate() {
+        let escaped: &[u8] = match b {
+            b'\\' => b"\\\\",
+            b'\t' => b"\\t",
+            b'\n' => b"\\n",
+            b'\r' => b"\\r",
+            b'\0' => b"\\0",
+            _ => continue,
+        };
+        writer.write_all(&bytes[start..i])?;
+        writer.write_all(escaped)?;
+        start = i + 1;
+    }
+
+    writer.write_all(&bytes[start..])?;
+
+    Ok(())
+}
+
+/// Writes the rows as tab-separated lines, `\N` being NULL.
+fn write_text_rows<W: Write, R: ValueReader>(
+    writer: &mut W,
+    rows: &mut RowIterator<R>,
+    syntax: &Syntax,
+) -> Result<()> {
+    let mut buf = String::new();
+
+    while let Some(row) = rows.next()? {
+        for (i, value) in row.enumerate() {
+            if i > 0 {
+                writer.write_all(b"\t")?;
+            }
+
+            match (value?, syntax) {
+                (Value::Null, _) => writer.write_all(b"\\N")?,
+                (Value::Bool(b), Syntax::Postgres) => {
+                    writer.write_all(if b { b"t" } else { b"f" })?
+                }
+                (Value::Bool(b), _) => writer.write_all(if b { b"1" } else { b"0" })?,
+                (Value::Int(i), _) => write!(writer, "{}", i)?,
+                (Value::Float(f), _) => write!(writer, "{}", f)?,
+                (Value::String(s), _) => write_escaped(writer, s.as_bytes())?,
+                // bytea hex format, with its backslash escaped
+                (Value::Binary(b), Syntax::Postgres) => write!(writer, "\\\\x{}", hex::encode(&b))?,
+                (Value::Binary(b), _) => write_escaped(writer, &b)?,
+                (value @ (Value::Object(_) | Value::Array(_)), _) => {
+                    buf.clear();
+                    unsafe { serde_json::to_writer(buf.as_mut_vec(), &value)? };
+                    write_escaped(writer, buf.as_bytes())?;
+                }
+            }
+        }
+
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+fn write_copy<W: Write, R: ValueReader>(
+    mut writer: W,
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<()> {
+    let names = rows
+        .fields()
+        .iter()
+        .map(|f| f.name.clone())
+        .collect::<Vec<_>>();
+
+    writer.write_all(b"COPY ")?;
+    write_table_name(&mut writer, opts)?;
+    writer.write_all(b" (")?;
+    write_identifiers(&mut writer, &opts.syntax, &names)?;
+    writer.write_all(b") FROM stdin;\n")?;
+
+    write_text_rows(&mut writer, rows, &opts.syntax)?;
+
+    writer.write_all(b"\\.\n")?;
+
+    Ok(())
+}
+
+/// Name of `encoding` in MySQL, for the `CHARACTER SET` of `LOAD DATA`.
+fn mysql_charset(encoding: Option<&Encoding>) -> Result<&'static str> {
+    let Some(Encoding(encoding)) = encoding else {
+        return Ok("utf8mb4");
+    };
+
+    let charset = match encoding.name() {
+        "UTF-8" => "utf8mb4",
+        "windows-1252" => "latin1",
+        "ISO-8859-2" => "latin2",
+        "ISO-8859-7" => "greek",
+        "ISO-8859-8" => "hebrew",
+        "windows-1254" => "latin5",
+        "ISO-8859-13" => "latin7",
+        "windows-1250" => "cp1250",
+        "windows-1251" => "cp1251",
+        "windows-1256" => "cp1256",
+        "windows-1257" => "cp1257",
+        "IBM866" => "cp866",
+        "KOI8-R" => "koi8r",
+        "KOI8-U" => "koi8u",
+        "Shift_JIS" => "sjis",
+        "EUC-JP" => "ujis",
+        "EUC-KR" => "euckr",
+        "GBK" => "gbk",
+        "gb18030" => "gb18030",
+        "Big5" => "big5",
+        // Including UTF-16, which `LOAD DATA` can't read
+        name => {
+            return Err(Error::OutputError(format!(
+                "`load_data` mode doesn't support the {} encoding",
+                name
+            )))
+        }
+    };
+
+    Ok(charset)
+}
+
+fn write_load_data<W: Write, R: ValueReader>(
+    mut writer: W,
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<()> {
+    let Some(data_file) = &opts.data_file else {
+        return Err(Error::OutputError(
+            "`load_data` mode requires `data_file`".into(),
+        ));
+    };
+
+    let charset = mysql_charset(opts.encoding.as_ref())?;
+
+    let file = std::io::BufWriter::new(std::fs::File::create(data_file)?);
+    let mut data = EncodingWriter::new(file, opts.encoding.as_ref(), opts.unmappable);
+    write_text_rows(&mut data, rows, &opts.syntax)?;
+    data.finish()?.flush()?;
+
+    let names = rows
+        .fields()
+        .iter()
+        .map(|f| f.name.clone())
+        .collect::<Vec<_>>();
+
+    writer.write_all(b"LOAD DATA LOCAL INFILE ")?;
+    write_value(
+        &mut writer,
+        &mut String::new(),
+        &Value::String(data_file.into()),
+        &opts.syntax,
+    )?;
+    match on_conflict(opts) {
+        Some(OnConflict::Replace) => writer.write_all(b" REPLACE")?,
+        Some(OnConflict::Ignore) => writer.write_all(b" IGNORE")?,
+        _ => {}
+    }
+    writer.write_all(b" INTO TABLE ")?;
+    write_table_name(&mut writer, opts)?;
+    write!(writer, " CHARACTER SET {}", charset)?;
+    writer.write_all(b" FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\'")?;
+    writer.write_all(b" LINES TERMINATED BY '\\n' (")?;
+    write_identifiers(&mut writer, &opts.syntax, &names)?;
+    writer.write_all(b");\n")?;
+
+    Ok(())
+}
+
+/// Checks the mode and conflict handling are supported by the syntax.
+fn validate(opts: &Options) -> Result<()> {
+    let error = |message: &str| Err(Error::OutputError(message.into()));
+
+    match (opts.mode, &opts.syntax) {
+        (Mode::Copy, Syntax::Postgres) | (Mode::LoadData, Syntax::Mysql) | (Mode::Insert, _) => {}
+        (Mode::Copy, _) => return error("`copy` mode requires Postgres syntax"),
+        (Mode::LoadData, _) => return error("`load_data` mode requires MySQL syntax"),
+    }
+
+    if !opts.indexes.is_empty()
+        && matches!(
+            opts.syntax,
+            Syntax::ClickHouse | Syntax::BigQuery | Syntax::Snowflake
+        )
+    {
+        return Err(Error::OutputError(format!(
+            "Indexes aren't available for {:?}",
+            opts.syntax
+        )));
+    }
+
+    if opts.normalize.is_some() && opts.mode == Mode::LoadData {
+        return error("`load_data` mode can't write normalized tables");
+    }
+
+    // The generated keys are the primary keys of the normalized tables, they
+    // start over on every run so conflicts on them would hit unrelated rows
+    if opts.normalize.is_some()
+        && (!opts.primary_key.is_empty()
+            || opts.infer_primary_key
+            || !opts.conflict_columns.is_empty()
+            || opts.on_conflict.is_some())
+    {
+        return error(
+            "`primary_key`, `infer_primary_key`, `conflict_columns` and `on_conflict` \
+             can't be used with `normalize`",
+        );
+    }
+
+    if opts.transaction && opts.syntax == Syntax::ClickHouse {
+        return error("ClickHouse doesn't support transactions");
+    }
+
+    if opts.on_conflict.is_some()
+        && !matches!(
+            opts.syntax,
+            Syntax::Postgres | Syntax::Mysql | Syntax::Sqlite
+        )
+    {
+        return Err(Error::OutputError(format!(
+            "Conflict handling isn't available for {:?}",
+            opts.syntax
+        )));
+    }
+
+    match (opts.mode, on_conflict(opts)) {
+        (_, None) => Ok(()),
+        (Mode::Copy, _) => error("`copy` mode can't handle conflicts"),
+        (Mode::LoadData, Some(OnConflict::Update)) => {
+            error("`load_data` mode can only ignore or replace conflicting rows")
+        }
+        (Mode::Insert, Some(OnConflict::Update))
+            if opts.syntax != Syntax::Mysql && opts.conflict_columns.is_empty() =>
+        {
+            error("Updating conflicting rows requires `conflict_columns`")
+        }
+        _ => Ok(()),
+    }
+}
+
+fn begin_transaction<W: Write>(writer: &mut W, opts: &Options) -> Result<()> {
+    if opts.transaction {
+        match opts.syntax {
+            // Oracle starts transactions implicitly
+            Syntax::Oracle => {}
+            Syntax::SqlServer | Syntax::BigQuery => writer.write_all(b"BEGIN TRANSACTION;\n")?,
+            _ => writer.write_all(b"BEGIN;\n")?,
+        }
+    }
+
+    Ok(())
+}
+
+fn commit_transaction<W: Write>(writer: &mut W, opts: &Options) -> Result<()> {
+    if opts.transaction {
+        writer.write_all(b"COMMIT;\n")?;
+    }
+
+    Ok(())
+}
+
+fn write_rows<W: Write, R: ValueReader>(
+    writer: &mut W,
+    rows: &mut RowIterator<R>,
+    opts: &Options,
+) -> Result<()> {
+    match opts.mode {
+        Mode::Insert => write_inserts(writer, rows, opts),
+        Mode::Copy => write_copy(writer, rows, opts),
+        Mode::LoadData => write_load_data(writer, rows, opts),
     }
+}
 
-    writer.write_all(b";\n")?;
+/// Writes every table of the normalised records, all the `CREATE TABLE`
+/// first and then the rows, parents before children.
+fn write_normalized<W: Write, R: ValueReader>(
+    mut writer: W,
+    mut reader: R,
+    opts: &Options,
+    normalize_opts: &normalize::Options,
+) -> Result<()> {
+    let tables = normalize::tables(&normalize::infer_schema(&mut reader)?, &opts.table_name)?;
+    let id_column = &normalize_opts.id_column;
+
+    let table_opts = tables
+        .iter()
+        .map(|table| Options {
+            table_name: table.name.clone(),
+            primary_key: vec![id_column.clone()],
+            drop_table: false,
+            ..opts.clone()
+        })
+        .collect::<Vec<_>>();
+
+    if opts.create_table {
+        // Each index goes on the tables having the column
+        let mut indexed = HashSet::new();
+
+        if opts.drop_table {
+            // Children first as they reference their parent
+            for table_opts in table_opts.iter().rev() {
+                write_drop_table(&mut writer, table_opts)?;
+            }
+        }
+
+        for (index, table) in tables.iter().enumerate() {
+            let parent_column = table.parent_column(&tables);
+            let foreign_key = match (table.parent, &parent_column) {
+                (Some(parent), Some(column)) => Some(ForeignKey {
+                    column,
+                    table: &tables[parent].name,
+                    references: id_column,
+                }),
+                _ => None,
+            };
+
+            let table_reader =
+                normalize::TableReader::new(&mut reader, &tables, index, normalize_opts);
+            let mut rows = table_reader.into_rows()?;
+            let (max_lengths, _) = scan_rows(&mut rows, &table_opts[index])?;
+            let fields = rows.fields().clone();
+
+            let indexes = opts
+                .indexes
+                .iter()
+                .filter(|c| fields.iter().any(|f| &f.name == *c))
+                .cloned()
+                .collect::<Vec<_>>();
+            indexed.extend(indexes.iter().cloned());
+
+            write_create_table(
+                &mut writer,
+                &Options {
+                    indexes,
+                    ..table_opts[index].clone()
+                },
+                &fields,
+                &max_lengths,
+                &table_opts[index].primary_key,
+                foreign_key.as_ref(),
+            )?;
+        }
+
+        if let Some(column) = opts.indexes.iter().find(|c| !indexed.contains(*c)) {
+            return Err(Error::OutputError(format!(
+                "Unknown column `{}` in `indexes`",
+                column
+            )));
+        }
+    }
+
+    begin_transaction(&mut writer, opts)?;
+
+    for (index, table_opts) in table_opts.iter().enumerate() {
+        let table_reader = normalize::TableReader::new(&mut reader, &tables, index, normalize_opts);
+        write_rows(&mut writer, &mut table_reader.into_rows()?, table_opts)?;
+    }
+
+    commit_transaction(&mut writer, opts)
+}
+
+pub fn write<W: Write, R: ValueReader>(writer: W, reader: R, opts: Options) -> Result<()> {
+    validate(&opts)?;
+
+    let mut writer = EncodingWriter::new(writer, opts.encoding.as_ref(), opts.unmappable);
+
+    match &opts.normalize {
+        Some(normalize_opts) => write_normalized(&mut writer, reader, &opts, normalize_opts)?,
+        None => write_single(&mut writer, reader, &opts)?,
+    }
+
+    writer.finish()?;
 
     Ok(())
 }
+
+fn write_single<W: Write, R: ValueReader>(mut writer: W, reader: R, opts: &Options) -> Result<()> {
+    let mut rows = reader.into_rows()?;
+
+    if opts.create_table {
+        let (max_lengths, primary_key) = scan_rows(&mut rows, opts)?;
+        let fields = rows.fields().clone();
+        write_create_table(&mut writer, opts, &fields, &max_lengths, &primary_key, None)?;
+    }
+
+    begin_transaction(&mut writer, opts)?;
+    write_rows(&mut writer, &mut rows, opts)?;
+    commit_transaction(&mut writer, opts)
+}
+
+#[test]
+fn sql_batches() {
+    let input = r#"[{"id":1,"name":"a"},{"id":2,"name":"b"},{"id":3,"name":"c"}]"#;
+    let write_with = |opts: Options| {
+        let reader = crate::json::reader::RecordReader::new(
+            input.as_bytes(),
+            crate::json::reader::Options::default(),
+        )
+        .unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, opts).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    let options = || Options {
+        create_table: false,
+        table_name: "t".into(),
+        batch_size: Some(2),
+        transaction: true,
+        column_names: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        write_with(options()),
+        "BEGIN;\n\
+         INSERT INTO \"t\" (\"id\",\"name\") VALUES\n(1,'a'),\n(2,'b');\n\
+         INSERT INTO \"t\" (\"id\",\"name\") VALUES\n(3,'c');\n\
+         COMMIT;\n"
+    );
+
+    assert_eq!(
+        write_with(Options {
+            statement_per_row: true,
+            transaction: false,
+            column_names: false,
+            ..options()
+        }),
+        "INSERT INTO \"t\" VALUES (1,'a');\n\
+         INSERT INTO \"t\" VALUES (2,'b');\n\
+         INSERT INTO \"t\" VALUES (3,'c');\n"
+    );
+
+    assert_eq!(
+        write_with(Options {
+            batch_size: None,
+            transaction: false,
+            on_conflict: Some(OnConflict::Update),
+            conflict_columns: vec!["id".into()],
+            ..options()
+        }),
+        "INSERT INTO \"t\" (\"id\",\"name\") VALUES\n(1,'a'),\n(2,'b'),\n(3,'c') \
+         ON CONFLICT (\"id\") DO UPDATE SET \"name\"=excluded.\"name\";\n"
+    );
+
+    assert_eq!(
+        write_with(Options {
+            syntax: Syntax::Mysql,
+            statement_per_row: true,
+            transaction: false,
+            column_names: false,
+            on_conflict: Some(OnConflict::Update),
+            ..options()
+        }),
+        "INSERT INTO `t` VALUES (1,'a') ON DUPLICATE KEY UPDATE `id`=VALUES(`id`),`name`=VALUES(`name`);\n\
+         INSERT INTO `t` VALUES (2,'b') ON DUPLICATE KEY UPDATE `id`=VALUES(`id`),`name`=VALUES(`name`);\n\
+         INSERT INTO `t` VALUES (3,'c') ON DUPLICATE KEY UPDATE `id`=VALUES(`id`),`name`=VALUES(`name`);\n"
+    );
+}
+
+#[test]
+fn sql_copy() {
+    let input = r#"{"id":1,"name":"tab\there\\","ok":true}
+{"id":2,"name":null,"ok":false}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+
+    let mut out = vec![];
+    write(
+        &mut out,
+        reader,
+        Options {
+            create_table: false,
+            table_name: "t".into(),
+            mode: Mode::Copy,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "COPY \"t\" (\"id\",\"name\",\"ok\") FROM stdin;\n\
+         1\ttab\\there\\\\\tt\n\
+         2\t\\N\tf\n\
+         \\.\n"
+    );
+}
+
+#[test]
+fn sql_load_data() {
+    let input = r#"{"id":1,"name":"caf\u00e9\t"}
+{"id":2,"name":null}"#;
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+
+    let write_with = |encoding: &str| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(
+            &mut out,
+            reader,
+            Options {
+                create_table: false,
+                table_name: "t".into(),
+                syntax: Syntax::Mysql,
+                mode: Mode::LoadData,
+                data_file: Some(path.to_str().unwrap().into()),
+                encoding: serde_json::from_str(&format!("\"{}\"", encoding)).unwrap(),
+                ..Default::default()
+            },
+        )
+        .map(|_| String::from_utf8(out).unwrap())
+    };
+
+    assert_eq!(
+        write_with("windows-1252").unwrap(),
+        format!(
+            "LOAD DATA LOCAL INFILE '{}' INTO TABLE `t` CHARACTER SET latin1 \
+             FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' (`id`,`name`);\n",
+            path.to_str().unwrap()
+        )
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), b"1\tcaf\xe9\\t\n2\t\\N\n");
+
+    assert!(write_with("utf-16le").is_err());
+}
+
+#[test]
+fn sql_dialects() {
+    let input = r#"{"id":1,"name":"it's \\","ok":true,"tags":["a"]}
+{"id":2,"name":null,"ok":false,"tags":[]}"#;
+    let write_with = |syntax: Syntax| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let opts = Options {
+            syntax,
+            create_table: false,
+            table_name: "t".into(),
+            ..Default::default()
+        };
+        let mut out = vec![];
+        write(&mut out, reader, opts).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(
+        write_with(Syntax::SqlServer),
+        "INSERT INTO [t] VALUES\n(1,N'it''s \\',1,N'[\"a\"]'),\n(2,NULL,0,N'[]');\n"
+    );
+    assert_eq!(
+        write_with(Syntax::Oracle),
+        "INSERT ALL\n\
+         INTO \"t\" VALUES (1,'it''s \\',1,'[\"a\"]')\n\
+         INTO \"t\" VALUES (2,NULL,0,'[]')\n\
+         SELECT 1 FROM DUAL;\n"
+    );
+    assert_eq!(
+        write_with(Syntax::BigQuery),
+        "INSERT INTO `t` VALUES\n(1,'it\\'s \\\\',TRUE,JSON '[\"a\"]'),\n(2,NULL,FALSE,JSON '[]');\n"
+    );
+    assert_eq!(
+        write_with(Syntax::Snowflake),
+        "INSERT INTO \"t\" SELECT column1,column2,column3,PARSE_JSON(column4) FROM VALUES\n\
+         (1,'it\\'s \\\\',TRUE,'[\"a\"]'),\n(2,NULL,FALSE,'[]');\n"
+    );
+}
+
+#[test]
+fn sql_create_table() {
+    let input = r#"{"id":1,"name":"abc","note":null}
+{"id":2,"name":"ab","note":"x"}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+
+    let mut out = vec![];
+    write(
+        &mut out,
+        reader,
+        Options {
+            syntax: Syntax::Mysql,
+            table_name: "t".into(),
+            schema: Some("s".into()),
+            if_not_exists: true,
+            drop_table: true,
+            infer_primary_key: true,
+            indexes: vec!["name".into()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "DROP TABLE IF EXISTS `s`.`t`;\n\
+         CREATE TABLE IF NOT EXISTS `s`.`t` (\n  \
+           `id` BIGINT NOT NULL,\n  \
+           `name` VARCHAR(3) NOT NULL,\n  \
+           `note` VARCHAR(1) NULL,\n  \
+           PRIMARY KEY (`id`)\n\
+         );\n\
+         CREATE INDEX `t_name_idx` ON `s`.`t` (`name`);\n\
+         \n\
+         INSERT INTO `s`.`t` VALUES\n(1,'abc',NULL),\n(2,'ab','x');\n"
+    );
+
+    let mut out = vec![];
+    let opts = Options {
+        syntax: Syntax::Oracle,
+        table_name: "it's".into(),
+        ..Default::default()
+    };
+    write_drop_table(&mut out, &opts).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "BEGIN\n  EXECUTE IMMEDIATE 'DROP TABLE \"it''s\"';\n\
+         EXCEPTION\n  WHEN OTHERS THEN\n    IF SQLCODE != -942 THEN\n      RAISE;\n    END IF;\n\
+         END;\n/\n"
+    );
+}
+
+#[test]
+fn sql_column_types() {
+    let string =
+        |syntax: Syntax, length: usize| column_type(&syntax, &DataType::String, length, false);
+
+    assert_eq!(string(Syntax::Mysql, 255), "VARCHAR(255)");
+    assert_eq!(string(Syntax::Mysql, 256), "TEXT");
+    assert_eq!(string(Syntax::Mysql, 20000), "LONGTEXT");
+
+    let long = "x".repeat(300);
+    let longer = "x".repeat(1000);
+    let input = format!(
+        r#"{{"code":"{}a","name":"{}a","note":"a"}}
+{{"code":"{}b","name":"{}b","note":"b"}}"#,
+        long, longer, long, longer
+    );
+    let reader =
+        crate::json::reader::RecordReader::new(input.into_bytes(), Default::default()).unwrap();
+    let mut out = vec![];
+    let opts = Options {
+        syntax: Syntax::Mysql,
+        table_name: "t".into(),
+        infer_primary_key: true,
+        indexes: vec!["name".into()],
+        ..Default::default()
+    };
+    write(&mut out, reader, opts).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with(
+        "CREATE TABLE `t` (\n  \
+           `code` VARCHAR(301) NOT NULL,\n  \
+           `name` TEXT NOT NULL,\n  \
+           `note` VARCHAR(1) NOT NULL,\n  \
+           PRIMARY KEY (`code`)\n\
+         );\n\
+         CREATE INDEX `t_name_idx` ON `t` (`name`(768));\n"
+    ));
+
+    let input = r#"{"emoji":"\ud83d\ude00"}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let mut out = vec![];
+    let opts = Options {
+        syntax: Syntax::SqlServer,
+        table_name: "t".into(),
+        ..Default::default()
+    };
+    write(&mut out, reader, opts).unwrap();
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains("[emoji] NVARCHAR(2)"));
+}
+
+#[test]
+fn sql_unknown_columns() {
+    let write_with = |opts: Options| {
+        let input = r#"{"id":1,"name":"abc"}"#;
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        write(vec![], reader, opts)
+    };
+
+    let error = write_with(Options {
+        table_name: "t".into(),
+        indexes: vec!["nmae".into()],
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(error.to_string().contains("`nmae`"));
+
+    let error = write_with(Options {
+        table_name: "t".into(),
+        primary_key: vec!["key".into()],
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(error.to_string().contains("`key`"));
+}
+
+#[test]
+fn sql_normalize_keys() {
+    let write_with = |opts: Options| {
+        let input = r#"{"id":1,"items":[{"sku":1}]}"#;
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        write(vec![], reader, opts)
+    };
+    let normalize = || Some(normalize::Options::default());
+
+    for opts in [
+        Options {
+            primary_key: vec!["id".into()],
+            ..Default::default()
+        },
+        Options {
+            infer_primary_key: true,
+            ..Default::default()
+        },
+        Options {
+            on_conflict: Some(OnConflict::Ignore),
+            ..Default::default()
+        },
+    ] {
+        let error = write_with(Options {
+            table_name: "t".into(),
+            normalize: normalize(),
+            ..opts
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("`normalize`"));
+    }
+
+    assert!(write_with(Options {
+        table_name: "t".into(),
+        normalize: normalize(),
+        ..Default::default()
+    })
+    .is_ok());
+}
+
+#[test]
+fn sql_escaping() {
+    let render = |syntax: Syntax, value: Value| {
+        let mut out = vec![];
+        write_value(&mut out, &mut String::new(), &value, &syntax).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    let identifier = |syntax: Syntax, id: &str| {
+        let mut out = vec![];
+        write_identifier(&mut out, &syntax, id).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    let string = |s: &'static str| Value::String(s.into());
+
+    let injection = "x\\'); DROP TABLE t; --";
+    assert_eq!(
+        render(Syntax::Mysql, string(injection)),
+        r"'x\\\'); DROP TABLE t; --'"
+    );
+    assert_eq!(
+        render(Syntax::Postgres, string(injection)),
+        r"'x\''); DROP TABLE t; --'"
+    );
+    assert_eq!(render(Syntax::Mysql, string("a\\")), r"'a\\'");
+    assert_eq!(render(Syntax::BigQuery, string("a\\")), r"'a\\'");
+    assert_eq!(render(Syntax::Sqlite, string("a\\")), r"'a\'");
+
+    assert_eq!(render(Syntax::Mysql, string("a\0b\x1a\n")), r"'a\0b\Z\n'");
+    assert_eq!(
+        render(Syntax::Postgres, string("it's\n\x01\\")),
+        r"E'it\'s\n\x01\\'"
+    );
+    assert_eq!(
+        render(Syntax::Sqlite, string("a'\0b")),
+        "'a''' || char(0) || 'b'"
+    );
+    assert_eq!(
+        render(Syntax::SqlServer, string("\0")),
+        "N'' + NCHAR(0) + N''"
+    );
+    assert_eq!(render(Syntax::Snowflake, string("\x7f'")), r"'\x7f\''");
+    assert!(write_value(
+        &mut vec![],
+        &mut String::new(),
+        &string("\0"),
+        &Syntax::Postgres
+    )
+    .is_err());
+
+    assert_eq!(
+        render(Syntax::Postgres, Value::Binary(vec![0, 255])),
+        r"'\x00ff'::bytea"
+    );
+
+    assert_eq!(identifier(Syntax::Postgres, "a\"b"), r#""a""b""#);
+    assert_eq!(identifier(Syntax::Mysql, "a`b"), "`a``b`");
+    assert_eq!(identifier(Syntax::BigQuery, "a`b\\"), r"`a\`b\\`");
+    assert_eq!(identifier(Syntax::SqlServer, "a]b"), "[a]]b]");
+    assert_eq!(identifier(Syntax::Sqlite, "a]\"b"), r#""a]""b""#);
+    assert!(write_identifier(&mut vec![], &Syntax::Mysql, "a\0").is_err());
+}
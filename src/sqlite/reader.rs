@@ -0,0 +1,261 @@
+use crate::reader::{Object, Value, ValueReader};
+use crate::sql::writer::{write_identifier, Syntax};
+use crate::{Error, Result};
+
+use crossbeam_channel::Receiver;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Rows sent over at a time
+const BATCH_SIZE: usize = 1000;
+
+type Batch = Result<Vec<Vec<Value<'static>>>>;
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Table to read, the first one of the database if neither this nor
+    /// `query` are given
+    pub table: Option<String>,
+    /// Query whose results are read instead of a table
+    pub query: Option<String>,
+}
+
+pub struct Reader {
+    path: PathBuf,
+    query: String,
+    columns: Vec<String>,
+    /// Statements borrow their connection and neither can be shared between
+    /// threads, so a thread owns both and steps through the query, started
+    /// on the first read
+    batches: Option<Receiver<Batch>>,
+    rows: VecDeque<Vec<Value<'static>>>,
+}
+
+fn from_sql(value: ValueRef) -> Value<'static> {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::Int(i),
+        ValueRef::Real(f) => Value::Float(f),
+        ValueRef::Text(s) => Value::String(Cow::Owned(String::from_utf8_lossy(s).into_owned())),
+        ValueRef::Blob(b) => Value::Binary(b.to_vec()),
+    }
+}
+
+fn open(path: &Path) -> Result<Connection> {
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(Error::input)
+}
+
+/// Runs the query once, calling `send` with each batch of rows until it
+/// returns false.
+fn step<F: FnMut(Vec<Vec<Value<'static>>>) -> bool>(
+    path: &Path,
+    query: &str,
+    mut send: F,
+) -> Result<()> {
+    let connection = open(path)?;
+    let mut statement = connection.prepare(query).map_err(Error::input)?;
+    let column_count = statement.column_count();
+    let mut rows = statement.raw_query();
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+
+    while let Some(row) = rows.next().map_err(Error::input)? {
+        let values = (0..column_count)
+            .map(|i| row.get_ref(i).map(from_sql))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(Error::input)?;
+        batch.push(values);
+
+        if batch.len() == BATCH_SIZE
+            && !send(std::mem::replace(
+                &mut batch,
+                Vec::with_capacity(BATCH_SIZE),
+            ))
+        {
+            return Ok(());
+        }
+    }
+
+    if !batch.is_empty() {
+        send(batch);
+    }
+
+    Ok(())
+}
+
+fn spawn(path: PathBuf, query: String) -> Receiver<Batch> {
+    let (sender, receiver) = crossbeam_channel::bounded(1);
+
+    std::thread::spawn(move || {
+        // Stops once the reader is reset or dropped
+        let result = step(&path, &query, |batch| sender.send(Ok(batch)).is_ok());
+
+        if let Err(e) = result {
+            let _ = sender.send(Err(e));
+        }
+    });
+
+    receiver
+}
+
+impl Reader {
+    pub fn new<P: AsRef<Path>>(path: P, opts: Options) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let connection = open(&path)?;
+
+        let query = match (opts.table, opts.query) {
+            (Some(_), Some(_)) => {
+                return Err(Error::InputError(
+                    "Only one of `table` and `query` can be given".into(),
+                ))
+            }
+            (None, Some(query)) => query,
+            (table, None) => {
+                let table = match table {
+                    Some(table) => table,
+                    None => connection
+                        .query_row(
+                            "SELECT name FROM sqlite_master \
+                             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+                             ORDER BY rowid LIMIT 1",
+                            [],
+                            |row| row.get::<_, String>(0),
+                        )
+                        .map_err(|_| Error::InputError("No tables found.".into()))?,
+                };
+
+                let mut query = b"SELECT * FROM ".to_vec();
+                write_identifier(&mut query, &Syntax::Sqlite, &table)?;
+                String::from_utf8(query).map_err(Error::input)?
+            }
+        };
+
+        let columns = connection
+            .prepare(&query)
+            .map_err(Error::input)?
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        Ok(Self {
+            path,
+            query,
+            columns,
+            batches: None,
+            rows: VecDeque::new(),
+        })
+    }
+}
+
+impl ValueReader for Reader {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        while self.rows.is_empty() {
+            let batches = self
+                .batches
+                .get_or_insert_with(|| spawn(self.path.clone(), self.query.clone()));
+
+            match batches.recv() {
+                Ok(batch) => self.rows.extend(batch?),
+                // The thread is done
+                Err(_) => return Ok(None),
+            }
+        }
+
+        Ok(self.rows.pop_front().map(|values| {
+            Value::Object(
+                self.columns
+                    .iter()
+                    .map(|c| Cow::Borrowed(c.as_str()))
+                    .zip(values)
+                    .collect::<Object>(),
+            )
+        }))
+    }
+
+    fn reset(&mut self) {
+        self.batches = None;
+        self.rows.clear();
+    }
+}
+
+#[test]
+fn sqlite_roundtrip() {
+    let input = r#"{"id":1,"name":"a","tags":["x"],"score":1.5}
+{"id":2,"name":null,"tags":[],"score":2}"#;
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    crate::sqlite::writer::write(
+        &path,
+        reader,
+        crate::sqlite::writer::Options {
+            table_name: "items".into(),
+            create_table: true,
+            drop_table: false,
+            primary_key: vec!["id".into()],
+            batch_size: Some(1),
+        },
+    )
+    .unwrap();
+
+    let mut rows = vec![];
+    let mut reader = Reader::new(&path, Default::default()).unwrap();
+    while let Some(row) = reader.next().unwrap() {
+        rows.push(serde_json::to_string(&row).unwrap());
+    }
+
+    assert_eq!(
+        rows,
+        vec![
+            r#"{"id":1,"name":"a","tags":"[\"x\"]","score":1.5}"#,
+            r#"{"id":2,"name":null,"tags":"[]","score":2.0}"#
+        ]
+    );
+
+    let opts = Options {
+        query: Some("SELECT count(*) AS n FROM items;".into()),
+        ..Default::default()
+    };
+    let mut reader = Reader::new(&path, opts).unwrap();
+    let row = reader.next().unwrap().unwrap();
+    assert_eq!(serde_json::to_string(&row).unwrap(), r#"{"n":2}"#);
+}
+
+#[test]
+fn sqlite_read_batches() {
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+
+    Connection::open(&path)
+        .unwrap()
+        .execute_batch(
+            "CREATE TABLE t (n INTEGER);
+             WITH RECURSIVE s(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM s WHERE n < 2500)
+             INSERT INTO t SELECT n FROM s;",
+        )
+        .unwrap();
+
+    let opts = Options {
+        query: Some("SELECT n FROM t ORDER BY n DESC -- newest first".into()),
+        ..Default::default()
+    };
+    let mut reader = Reader::new(&path, opts).unwrap();
+
+    for _ in 0..2 {
+        let mut numbers = vec![];
+        while let Some(row) = reader.next().unwrap() {
+            match row {
+                Value::Object(row) => numbers.push(row["n"].clone().into_owned()),
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(numbers.len(), 2500);
+        assert!(matches!(numbers[0], Value::Int(2500)));
+        assert!(matches!(numbers[2499], Value::Int(1)));
+        reader.reset();
+    }
+}
@@ -0,0 +1,169 @@
+use crate::reader::ValueReader;
+use crate::sql::writer::{write_create_table, write_identifier, Syntax};
+use crate::{sql, Error, Result, Value};
+
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use rusqlite::Connection;
+use std::path::Path;
+
+#[derive(Debug, serde::Deserialize)]
+pub struct Options {
+    pub table_name: String,
+    #[serde(default = "default_true")]
+    pub create_table: bool,
+    /// Drop the table first if it already exists
+    #[serde(default)]
+    pub drop_table: bool,
+    #[serde(default)]
+    pub primary_key: Vec<String>,
+    /// Rows inserted per transaction, all of them in one when not set
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Options of the SQL writer generating the same `CREATE TABLE` as the
+/// `sqlite` syntax.
+fn sql_options(opts: &Options) -> sql::writer::Options {
+    sql::writer::Options {
+        syntax: Syntax::Sqlite,
+        table_name: opts.table_name.clone(),
+        if_not_exists: true,
+        drop_table: opts.drop_table,
+        primary_key: opts.primary_key.clone(),
+        ..Default::default()
+    }
+}
+
+fn to_sql<'a>(value: &'a Value) -> Result<ToSqlOutput<'a>> {
+    Ok(match value {
+        Value::Null => ToSqlOutput::Borrowed(ValueRef::Null),
+        Value::Bool(b) => ToSqlOutput::Borrowed(ValueRef::Integer(*b as i64)),
+        Value::Int(i) => ToSqlOutput::Borrowed(ValueRef::Integer(*i)),
+        Value::Float(f) => ToSqlOutput::Borrowed(ValueRef::Real(*f)),
+        Value::String(s) => ToSqlOutput::Borrowed(ValueRef::Text(s.as_bytes())),
+        Value::Binary(b) => ToSqlOutput::Borrowed(ValueRef::Blob(b)),
+        Value::Object(_) | Value::Array(_) => {
+            ToSqlOutput::Owned(rusqlite::types::Value::Text(serde_json::to_string(value)?))
+        }
+    })
+}
+
+/// Writes the records into the SQLite database at `path`, creating it if
+/// needed, with one prepared `INSERT` per row.
+pub fn write<P: AsRef<Path>, R: ValueReader>(path: P, reader: R, opts: Options) -> Result<()> {
+    let mut connection = Connection::open(path).map_err(Error::output)?;
+    let mut rows = reader.into_rows()?;
+    let fields = rows.fields().clone();
+
+    if opts.create_table {
+        // Lengths only matter to the VARCHAR of other databases
+        let max_lengths = vec![0; fields.len()];
+        let mut create = vec![];
+        write_create_table(
+            &mut create,
+            &sql_options(&opts),
+            &fields,
+            &max_lengths,
+            &opts.primary_key,
+            None,
+        )?;
+        let create = String::from_utf8(create).map_err(Error::output)?;
+        connection.execute_batch(&create).map_err(Error::output)?;
+    }
+
+    let mut insert = vec![];
+    insert.extend_from_slice(b"INSERT INTO ");
+    write_identifier(&mut insert, &Syntax::Sqlite, &opts.table_name)?;
+    insert.extend_from_slice(b" (");
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            insert.push(b',');
+        }
+        write_identifier(&mut insert, &Syntax::Sqlite, &field.name)?;
+    }
+    insert.extend_from_slice(b") VALUES (");
+    insert.extend_from_slice(vec!["?"; fields.len()].join(",").as_bytes());
+    insert.extend_from_slice(b")");
+    let insert = String::from_utf8(insert).map_err(Error::output)?;
+
+    let batch_size = opts.batch_size.unwrap_or(usize::MAX).max(1);
+    let mut done = false;
+
+    while !done {
+        let transaction = connection.transaction().map_err(Error::output)?;
+        {
+            let mut statement = transaction.prepare_cached(&insert).map_err(Error::output)?;
+            let mut count = 0;
+
+            while count < batch_size {
+                let Some(row) = rows.next()? else {
+                    done = true;
+                    break;
+                };
+
+                for (index, value) in row.enumerate() {
+                    let value = value?;
+                    statement
+                        .raw_bind_parameter(index + 1, to_sql(&value)?)
+                        .map_err(Error::output)?;
+                }
+                statement.raw_execute().map_err(Error::output)?;
+                count += 1;
+            }
+        }
+        transaction.commit().map_err(Error::output)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sqlite_write_existing_table() {
+    let input = r#"{"id":1,"name":"a","tags":["x"]}
+{"id":2,"name":"b","tags":[]}"#;
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+
+    Connection::open(&path)
+        .unwrap()
+        .execute_batch("CREATE TABLE t (tags TEXT, name TEXT, id INTEGER)")
+        .unwrap();
+
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    let opts = Options {
+        table_name: "t".into(),
+        create_table: false,
+        drop_table: false,
+        primary_key: vec![],
+        batch_size: None,
+    };
+    write(&path, reader, opts).unwrap();
+
+    let connection = Connection::open(&path).unwrap();
+    let mut statement = connection
+        .prepare("SELECT id, name, tags FROM t ORDER BY id")
+        .unwrap();
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .unwrap()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![
+            (1, "a".to_string(), r#"["x"]"#.to_string()),
+            (2, "b".to_string(), "[]".to_string())
+        ]
+    );
+}
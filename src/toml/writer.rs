@@ -0,0 +1,227 @@
+use crate::reader::{Object, ValueReader};
+use crate::{Error, Result, Value};
+
+use std::io::Write;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    pub table_name: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            table_name: "record".into(),
+        }
+    }
+}
+
+pub fn write_key<W: Write>(writer: &mut W, key: &str) -> Result<()> {
+    let is_bare = !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+
+    if is_bare {
+        writer.write_all(key.as_bytes())?;
+        Ok(())
+    } else {
+        write_string(writer, key)
+    }
+}
+
+pub fn write_string<W: Write>(writer: &mut W, s: &str) -> Result<()> {
+    writer.write_all(b"\"")?;
+
+    for c in s.chars() {
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            '\t' => writer.write_all(b"\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04X}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+
+    writer.write_all(b"\"")?;
+
+    Ok(())
+}
+
+/// Writes a value in its inline form. Nulls have no TOML representation, the
+/// keys having one are left out and nulls in arrays are an error as leaving
+/// them out would shift the other items.
+pub fn write_value<W: Write>(writer: &mut W, value: &Value) -> Result<()> {
+    match value {
+        Value::Int(i) => write!(writer, "{}", i)?,
+        Value::String(s) => write_string(writer, s)?,
+        Value::Float(f) if f.is_nan() => writer.write_all(b"nan")?,
+        Value::Float(f) if f.is_infinite() => {
+            writer.write_all(if *f > 0.0 { b"inf" } else { b"-inf" })?
+        }
+        Value::Float(f) => write!(writer, "{:?}", f)?,
+        Value::Bool(b) => write!(writer, "{}", b)?,
+        Value::Array(values) => {
+            if values.contains(&Value::Null) {
+                return Err(Error::OutputError("TOML arrays can't contain nulls".into()));
+            }
+
+            writer.write_all(b"[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    writer.write_all(b", ")?;
+                }
+                write_value(writer, value)?;
+            }
+            writer.write_all(b"]")?;
+        }
+        Value::Object(object) => {
+            writer.write_all(b"{")?;
+            let mut is_first = true;
+            for (key, value) in object.iter().filter(|(_, v)| **v != Value::Null) {
+                if is_first {
+                    is_first = false;
+                } else {
+                    writer.write_all(b",")?;
+                }
+                writer.write_all(b" ")?;
+                write_key(writer, key)?;
+                writer.write_all(b" = ")?;
+                write_value(writer, value)?;
+            }
+            writer.write_all(if is_first { b"}" } else { b" }" })?;
+        }
+        Value::Null => {}
+        Value::Binary(b) => write_string(writer, &hex::encode(b))?,
+    };
+
+    Ok(())
+}
+
+fn is_table_array(value: &Value) -> bool {
+    match value {
+        Value::Array(values) => !values.is_empty() && values.iter().all(Value::is_object),
+        _ => false,
+    }
+}
+
+fn write_header<W: Write>(writer: &mut W, path: &[&str], is_array: bool) -> Result<()> {
+    writer.write_all(if is_array { b"[[" } else { b"[" })?;
+
+    for (i, key) in path.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b".")?;
+        }
+        write_key(writer, key)?;
+    }
+
+    writer.write_all(if is_array { b"]]\n" } else { b"]\n" })?;
+
+    Ok(())
+}
+
+fn write_table<'a, W: Write>(
+    writer: &mut W,
+    path: &mut Vec<&'a str>,
+    object: &'a Object,
+    is_array: bool,
+) -> Result<()> {
+    write_header(writer, path, is_array)?;
+
+    for (key, value) in object {
+        if matches!(value, Value::Null | Value::Object(_)) || is_table_array(value) {
+            continue;
+        }
+
+        write_key(writer, key)?;
+        writer.write_all(b" = ")?;
+        write_value(writer, value)?;
+        writer.write_all(b"\n")?;
+    }
+
+    for (key, value) in object {
+        match value {
+            Value::Object(inner) => {
+                writer.write_all(b"\n")?;
+                path.push(key);
+                write_table(writer, path, inner, false)?;
+                path.pop();
+            }
+            Value::Array(values) if is_table_array(value) => {
+                path.push(key);
+                for inner in values.iter().filter_map(Value::as_object) {
+                    writer.write_all(b"\n")?;
+                    write_table(writer, path, inner, true)?;
+                }
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write<W: Write, R: ValueReader>(mut writer: W, mut reader: R, opts: Options) -> Result<()> {
+    let mut is_first = true;
+
+    while let Some(row) = reader.next()? {
+        let Value::Object(object) = row else {
+            return Err(Error::OutputError(
+                "TOML output requires every record to be an object".into(),
+            ));
+        };
+
+        if is_first {
+            is_first = false;
+        } else {
+            writer.write_all(b"\n")?;
+        }
+
+        write_table(
+            &mut writer,
+            &mut vec![opts.table_name.as_str()],
+            &object,
+            true,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn toml_write() {
+    let input = r#"{"id":1,"name":"a \"b\"","tags":["x","y"],"owner":{"first name":"c"},"items":[{"n":1},{"n":2}],"gone":null}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+
+    let mut out = vec![];
+    write(&mut out, reader, Default::default()).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "[[record]]\n\
+         id = 1\n\
+         name = \"a \\\"b\\\"\"\n\
+         tags = [\"x\", \"y\"]\n\
+         \n\
+         [record.owner]\n\
+         \"first name\" = \"c\"\n\
+         \n\
+         [[record.items]]\n\
+         n = 1\n\
+         \n\
+         [[record.items]]\n\
+         n = 2\n"
+    );
+
+    let reader = crate::json::reader::RecordReader::new(&b"[1]"[..], Default::default()).unwrap();
+    assert!(write(vec![], reader, Default::default()).is_err());
+
+    let input = r#"{"tags":["x",null]}"#;
+    let reader =
+        crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+    assert!(write(vec![], reader, Default::default()).is_err());
+}
@@ -1,7 +1,16 @@
+use crate::io::{Encoding, EncodingWriter, Unmappable};
 use crate::reader::ValueReader;
 use crate::{Result, Value};
 use std::io::Write;
 
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// Output encoding, UTF-8 when not set
+    pub encoding: Option<Encoding>,
+    pub unmappable: Unmappable,
+}
+
 pub fn write_value<W: Write>(writer: &mut W, buf: &mut String, value: &Value) -> Result<()> {
     match value {
         Value::Int(i) => write!(writer, "{}", i),
@@ -18,17 +27,19 @@ pub fn write_value<W: Write>(writer: &mut W, buf: &mut String, value: &Value) ->
             html_escape::encode_text_to_writer(buf, writer)
         }
         Value::Null => write!(writer, "NULL"),
-        Value::Binary(_) => todo!(),
+        Value::Binary(b) => write!(writer, "{}", hex::encode(b)),
     }
     .map_err(Into::into)
 }
 
-pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R) -> Result<()> {
+pub fn write<W: Write, R: ValueReader>(writer: W, reader: R, opts: Options) -> Result<()> {
+    let mut writer = EncodingWriter::new(writer, opts.encoding.as_ref(), opts.unmappable);
     let mut buf = String::new();
     let mut rows = reader.into_rows()?;
     let ws = regex::Regex::new(r"[^\w]+").unwrap();
 
-    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8" ?>"#)?;
+    let encoding = opts.encoding.map_or("UTF-8", |e| e.0.name());
+    writeln!(writer, r#"<?xml version="1.0" encoding="{}" ?>"#, encoding)?;
     writeln!(writer, "<root>")?;
 
     let fields = rows
@@ -48,6 +59,7 @@ pub fn write<W: Write, R: ValueReader>(mut writer: W, reader: R) -> Result<()> {
     }
 
     writeln!(writer, "</root>")?;
+    writer.finish()?;
 
     Ok(())
 }
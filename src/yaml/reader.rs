@@ -0,0 +1,74 @@
+use crate::{
+    reader::{Value, ValueReader},
+    Error, Result,
+};
+
+use serde::Deserialize;
+
+/// Reads every document of a YAML stream, a top-level sequence yields one
+/// record per item and any other document is a record on its own.
+pub struct Reader {
+    index: usize,
+    records: Vec<Value<'static>>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {}
+
+impl ValueReader for Reader {
+    fn next(&mut self) -> Result<Option<Value<'_>>> {
+        let value = self.records.get(self.index).cloned();
+        self.index += 1;
+        Ok(value)
+    }
+
+    fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+impl Reader {
+    pub fn new<T: AsRef<[u8]>>(data: T, _opts: Options) -> Result<Self> {
+        let mut records = vec![];
+
+        for document in serde_yaml::Deserializer::from_slice(data.as_ref()) {
+            match Value::deserialize(document).map_err(Error::input)? {
+                Value::Null => {}
+                Value::Array(values) => records.extend(values.into_iter().map(Value::into_owned)),
+                value => records.push(value.into_owned()),
+            }
+        }
+
+        Ok(Self { index: 0, records })
+    }
+}
+
+#[test]
+fn yaml_documents() {
+    let input = "
+- name: web
+  replicas: 2
+- name: worker
+  replicas: ~
+---
+name: cron
+replicas: 1.5
+labels: [a, b]
+";
+
+    let mut reader = Reader::new(input, Default::default()).unwrap();
+
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"name": "web", "replicas": 2})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"name": "worker", "replicas": null})
+    );
+    assert_eq!(
+        reader.next().unwrap().unwrap(),
+        crate::value!({"name": "cron", "replicas": 1.5, "labels": ["a", "b"]})
+    );
+    assert!(reader.next().unwrap().is_none());
+}
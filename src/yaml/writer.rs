@@ -0,0 +1,99 @@
+use crate::reader::ValueReader;
+use crate::{Error, Result};
+
+use serde::ser::{SerializeSeq, Serializer};
+use std::io::Write;
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub enum Mode {
+    #[default]
+    #[serde(rename = "sequence")]
+    Sequence,
+    #[serde(rename = "documents")]
+    Documents,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct Options {
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+pub fn write<W: Write, R: ValueReader>(mut inner: W, mut reader: R, opts: Options) -> Result<()> {
+    match opts.mode {
+        Mode::Documents => {
+            while let Some(row) = reader.next()? {
+                let document = serde_yaml::to_string(&row).map_err(Error::output)?;
+                inner.write_all(b"---\n")?;
+                inner.write_all(document.as_bytes())?;
+            }
+        }
+        Mode::Sequence => {
+            let mut serializer = serde_yaml::Serializer::new(inner);
+            let mut sequence = serializer.serialize_seq(None).map_err(Error::output)?;
+
+            while let Some(row) = reader.next()? {
+                sequence.serialize_element(&row).map_err(Error::output)?;
+            }
+
+            sequence.end().map_err(Error::output)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn yaml_write() {
+    use crate::yaml::reader::Reader;
+    use crate::Value;
+
+    let input = r#"{"name":"web","motd":"hello\nworld\n","ports":[80,443]}
+{"name":"worker","motd":null,"ports":[]}"#;
+    let write_with = |mode: Mode| {
+        let reader =
+            crate::json::reader::RecordReader::new(input.as_bytes(), Default::default()).unwrap();
+        let mut out = vec![];
+        write(&mut out, reader, Options { mode }).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    let sequence = write_with(Mode::Sequence);
+    assert_eq!(
+        sequence,
+        "- name: web\n  motd: |\n    hello\n    world\n  ports:\n  - 80\n  - 443\n\
+         - name: worker\n  motd: null\n  ports: []\n"
+    );
+
+    for output in [sequence, write_with(Mode::Documents)] {
+        let mut reader = Reader::new(output, Default::default()).unwrap();
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"name": "web", "motd": "hello\nworld\n", "ports": [80, 443]})
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            crate::value!({"name": "worker", "motd": null, "ports": []})
+        );
+        assert!(reader.next().unwrap().is_none());
+    }
+
+    let reader = crate::json::reader::RecordReader::new(&b""[..], Default::default()).unwrap();
+    let mut out = vec![];
+    write(&mut out, reader, Default::default()).unwrap();
+    assert_eq!(out, b"[]\n");
+}
+
+#[test]
+fn yaml_binary() {
+    let input = r#"{"data":{"$binary":{"base64":"yv4=","subType":"00"}}}"#;
+    let opts = crate::json::reader::Options {
+        extended_json: true,
+        ..Default::default()
+    };
+    let reader = crate::json::reader::RecordReader::new(input.as_bytes(), opts).unwrap();
+
+    let mut out = vec![];
+    write(&mut out, reader, Default::default()).unwrap();
+    assert_eq!(out, b"- data: cafe\n");
+}